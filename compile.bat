@@ -1 +1 @@
-cargo build --target wasm32-unknown-unknown --release && wasm-bindgen target/wasm32-unknown-unknown/release/neural-network-evolution.wasm --target web --out-dir wasm && echo: && echo OK
+cargo build --lib --target wasm32-unknown-unknown --release && wasm-bindgen target/wasm32-unknown-unknown/release/neural_network_evolution.wasm --target web --out-dir wasm --out-name neural-network-evolution && echo: && echo OK
@@ -1,13 +1,16 @@
 "use strict";
 
-import init, {run, print_agent_at} from '../wasm/neural-network-evolution.js'
+import init, {World} from '../wasm/neural-network-evolution.js'
 
 const FPS = 60;
 
 let canvas;
 let loop;
+let world;
 
 window.inverseSpawnRate = 32;
+window.colourBySpecies  = false; // needs speciation enabled, see setConfig({species: {...}})
+window.seed             = undefined; // set to e.g. 1234n before loading to replay a run
 
 window.stopAll = function stopAll() {
 	clearInterval(loop);
@@ -17,37 +20,165 @@ window.runAtFPS = function runAtFPS(fps) {
 	stopAll();
 	
 	console.log(`Running game at ${fps} FPS.`);
-	loop = setInterval(function() {
-		run(window.inverseSpawnRate)
-	}, 1000/fps);
+	loop = setInterval(tick, 1000/fps);
+}
+
+window.getConfig = function getConfig() {
+	return JSON.parse(world.config_json);
+}
+
+window.setConfig = function setConfig(config) {
+	world.set_config_json(JSON.stringify({...getConfig(), ...config}));
+	window.inverseSpawnRate = world.inverse_spawn_rate;
+	fitCanvas();
+}
+
+// Size the canvas to the world, which need not be square
+function fitCanvas() {
+	const canvasElem = document.querySelector("canvas");
+
+	canvasElem.width  = world.width;
+	canvasElem.height = world.height;
+}
+
+function download(data, type, filename) {
+	const link = document.createElement("a");
+
+	link.href     = URL.createObjectURL(new Blob([data], {type}));
+	link.download = filename;
+	link.click();
+
+	URL.revokeObjectURL(link.href);
+}
+
+function upload(accept, onFile) {
+	const input = document.createElement("input");
+
+	input.type     = "file";
+	input.accept   = accept;
+	input.onchange = () => onFile(input.files[0]);
+	input.click();
+}
+
+window.saveSnapshot = function saveSnapshot() {
+	download(world.save_snapshot(), "application/json", `world-${world.seed}-tick${world.tick}.json`);
+}
+
+window.loadSnapshot = function loadSnapshot() {
+	upload(".json,application/json", async function(file) {
+		try {
+			const loaded = World.load_snapshot(await file.text());
+
+			world.free();
+			world = loaded;
+			window.inverseSpawnRate = world.inverse_spawn_rate;
+			fitCanvas();
+
+			console.log(`Loaded snapshot of world ${world.seed} at tick ${world.tick}.`);
+		} catch (err) {
+			console.error(`Failed to load snapshot: ${err}`);
+		}
+	});
+}
+
+window.downloadStats = function downloadStats(format = "csv") {
+	if (format === "json") {
+		download(world.stats_json(), "application/json", `stats-${world.seed}.json`);
+	} else {
+		download(world.stats_csv(), "text/csv", `stats-${world.seed}.csv`);
+	}
+}
+
+window.downloadLineage = function downloadLineage(format = "newick") {
+	if (format === "json") {
+		download(world.lineage_json(), "application/json", `lineage-${world.seed}.json`);
+	} else {
+		download(world.lineage_newick(), "text/plain", `lineage-${world.seed}.nwk`);
+	}
+}
+
+window.downloadSpecies = function downloadSpecies() {
+	download(world.species_json(), "application/json", `species-${world.seed}.json`);
+}
+
+window.downloadHallOfFame = function downloadHallOfFame() {
+	download(world.hall_of_fame_json(), "application/json", `hall-of-fame-${world.seed}.json`);
+}
+
+window.importGenome = function importGenome() {
+	upload(".json,.genome", async function(file) {
+		try {
+			if (file.name.endsWith(".json")) {
+				world.spawn_from_genome(await file.text());
+			} else {
+				world.spawn_from_genome_bytes(new Uint8Array(await file.arrayBuffer()));
+			}
+
+			console.log(`Spawned agent from genome ${file.name}.`);
+		} catch (err) {
+			console.error(`Failed to import genome: ${err}`);
+		}
+	});
+}
+
+function tick() {
+	world.inverse_spawn_rate = window.inverseSpawnRate;
+	world.colour_by_species  = window.colourBySpecies;
+	world.step();
+	world.draw(canvas);
 }
 
 window.onload = function init() {
 	let canvasElem = document.querySelector("canvas");
 
-	// Print agent debug info on click
+	// Print agent debug info on click, or download its genome on shift-click
 	canvasElem.addEventListener("mousedown", function(e) {
 		const rect   = canvasElem.getBoundingClientRect();
-		const [x, y] = [event.clientX - rect.left, event.clientY - rect.top];
+		const [x, y] = [e.clientX - rect.left, e.clientY - rect.top];
 
-		print_agent_at(x, y)
+		if (e.shiftKey) {
+			const genome = world.export_genome_at(x, y);
+			if (genome !== undefined) {
+				download(genome, "application/json", `genome-tick${world.tick}.json`);
+			}
+		} else {
+			world.print_agent_at(x, y)
+		}
 	});
 
 	canvas = canvasElem.getContext("2d");
 }
 
-window.draw_bg = function draw_bg() {
+window.draw_bg = function draw_bg(canvas) {
+	const [width, height] = [canvas.canvas.width, canvas.canvas.height];
+
 	// Clear canvas
-	canvas.clearRect(0, 0, 600, 600);
+	canvas.clearRect(0, 0, width, height);
 	
 	// Draw background
 	canvas.fillStyle = "#eee";
-	canvas.fillRect(0, 0, 600, 600);
+	canvas.fillRect(0, 0, width, height);
 }
 
-window.draw_agent = function draw_agent(r, g, b, x, y, size) {
+window.draw_agent = function draw_agent(canvas, r, g, b, x, y, size, angle) {
 	canvas.fillStyle = `rgb(${r}, ${g}, ${b})`;
-	canvas.fillRect(x, y, size, size);
+	canvas.beginPath();
+	canvas.arc(x, y, size/2, 0, 2*Math.PI);
+	canvas.fill();
+
+	// Heading indicator, from the centre to the edge
+	canvas.strokeStyle = "#000";
+	canvas.beginPath();
+	canvas.moveTo(x, y);
+	canvas.lineTo(x + Math.cos(angle)*size/2, y + Math.sin(angle)*size/2);
+	canvas.stroke();
+}
+
+window.draw_pellet = function draw_pellet(canvas, x, y, size) {
+	canvas.fillStyle = "#4a4";
+	canvas.beginPath();
+	canvas.arc(x, y, size/2, 0, 2*Math.PI);
+	canvas.fill();
 }
 
 window.draw_neural_network = function draw_neural_network(s) {
@@ -55,10 +186,13 @@ window.draw_neural_network = function draw_neural_network(s) {
 }
 
 init().then(() => {
+	world = new World(window.seed);
+	fitCanvas();
+	console.log(`World seed: ${world.seed}`);
+
 	console.log("Finished loading WebAssembly.");
-	console.log("Available toggles: [stopAll(), runAtFPS(fps), inverseSpawnRate].");
+	console.log("Available toggles: [stopAll(), runAtFPS(fps), inverseSpawnRate, colourBySpecies, getConfig(), setConfig({...}), saveSnapshot(), loadSnapshot(), importGenome(), downloadStats(format), downloadLineage(format), downloadSpecies(), downloadHallOfFame()].");
+	console.log("Shift-click an agent to download its genome.");
 	console.log(`Running game at ${FPS} FPS.`);
-	loop = setInterval(function() {
-		run(window.inverseSpawnRate)
-	}, 1000/FPS);
+	loop = setInterval(tick, 1000/FPS);
 })
@@ -1,40 +1,82 @@
-use std::{fmt, f64::consts::PI};
+use std::{fmt, f64::consts::{PI, SQRT_2}};
 
-use crate::helpers::*;
+use serde::{Deserialize, Serialize};
 
-const INPS: usize = 4;
-const OUTS: usize = 2;
+use crate::{config::SimConfig, crossover::{self, Innovations}, game, helpers::*, input::{self, Input}, output::{self, Output}, lineage::AgentIds, metabolism::{self, Activity}, neuron_model::*, plasticity, spatial::Grid, species::Speciation};
 
-macro_rules! arr {
-	($elem:expr) => (core::array::from_fn(|_| $elem))
-}
+const MAX_DELAY: usize = 8; // max conduction delay of a connection, in ticks
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Agent {
+	pub id     : u64,
+	pub parent : Option<u64>,
+	#[serde(default)]
+	pub mate   : Option<u64>, // other parent, if born through mating
+
+	#[serde(default)]
+	pub species: Option<u64>, // as of the last speciation
+
 	pub brain : Brain,
 	pub body  : Body,
 	pub alive : bool,
 
-	inv_split_freq: usize
+	pub(crate) inv_split_freq: usize,
+
+	#[serde(default)]
+	pub(crate) mate_cooldown: u64, // ticks until able to mate again
+
+	#[serde(default)]
+	pub(crate) split_cooldown: u64, // ticks until able to split again, with `SimConfig::split_output`
+
+	#[serde(default)]
+	pub(crate) since_meal: u64, // ticks since last eating another agent or a pellet
+
+	#[serde(default)]
+	pub achievements: Achievements
+}
+
+/// What an agent has accomplished during its life so far
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Achievements {
+	pub max_size  : f64,
+	pub age       : u64, // in ticks
+	pub kills     : usize,
+	pub offspring : usize
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Death {
+	Starved, // shrunk below `SimConfig::death_size`
+	Eaten
 }
 
 
 ////////////////////////////////////////////////////////////////
 ////////////////////////////////////////////////////////////////
 
-/// neurons_inp: [size_diff, dist, angle_to_near, touching_edge] normalised to [-1, 1]
-/// neurons_out: [mov, rot] normalised to [-1, 1]
-#[derive(Clone)]
+/// neurons_inp: sensors normalised to [-1, 1], see `input::Input`
+/// neurons_out: actuators normalised to [-1, 1], see `output::Output`
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Brain {
-	neurons_inp: [Neuron; INPS],
-	neurons_hid: Vec<Neuron>,
-	neurons_out: [Neuron; OUTS],
+	pub(crate) neurons_inp: Vec<Neuron>,
+	pub(crate) neurons_hid: Vec<Neuron>,
+	pub(crate) neurons_out: Vec<Neuron>,
+
+	pub(crate) input_kinds  : Vec<Input>,  // what each input neuron senses
+	pub(crate) output_kinds : Vec<Output>, // what each output neuron controls
+
+	pub(crate) generation: usize, // for debugging/display
+
+	#[serde(default)]
+	pub(crate) in_flight: Vec<Spike>, // spikes on their way through delayed connections
 
-	generation: usize // for debugging/display
+	#[serde(default)]
+	pub(crate) clock: u64 // ticks this brain has been running, for spike timing
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Neuron {
+	#[serde(with = "non_finite")]
 	pub excitation: f64,
 	pub tick_drain: f64,
 
@@ -42,17 +84,48 @@ pub struct Neuron {
 
 	pub next_conn: Vec<OutwardConn>,
 
-	reachable: bool,
+	#[serde(default)]
+	pub innovation: u64, // historical marking, see `Innovations`
 
-	inv_mut: usize
+	#[serde(default)]
+	pub learn_rate: f64, // scales plasticity of outgoing connections
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub model: Option<ModelKind>, // evolved firing rule, if not just the configured one
+
+	#[serde(default, with = "non_finite_array")]
+	pub(crate) state: [f64; 2], // model-specific, e.g. Izhikevich membrane potential & recovery
+
+	pub(crate) reachable: bool,
+
+	#[serde(default)]
+	pub(crate) last_fired: Option<u64>,
+
+	pub(crate) inv_mut: usize
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct OutwardConn {
 	pub dest_index: usize,
-	pub speed: usize, // currently unused
+	pub speed: usize, // conduction delay in ticks
 	pub weight: f64,
-	pub relu: bool
+	pub relu: bool,
+
+	#[serde(default)]
+	pub innovation: u64 // historical marking, see `Innovations`
+}
+
+
+////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////
+
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Spike {
+	dest_index : usize,
+	ticks_left : usize,
+	#[serde(with = "non_finite")]
+	amount     : f64
 }
 
 
@@ -60,26 +133,37 @@ pub struct OutwardConn {
 ////////////////////////////////////////////////////////////////
 
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Body {
 	pub colour: Colour,
 
-	pub pos   : Pos,
-	pub size  : f64,
+	pub pos   : Pos, // centre
+	pub size  : f64, // diameter
+	#[serde(with = "non_finite")]
 	pub angle : f64,
 
+	#[serde(with = "non_finite")]
 	pub mov: f64,
-	pub rot: f64
+	#[serde(with = "non_finite")]
+	pub rot: f64,
+
+	#[serde(default, with = "non_finite")]
+	pub split: f64,
+	#[serde(default, with = "non_finite")]
+	pub child_ratio: f64,
+
+	#[serde(default)]
+	pub energy: f64 // see `MetabolismConfig`
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Colour {
 	pub r: usize,
 	pub g: usize,
 	pub b: usize
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Pos {pub x: f64, pub y: f64}
 
 
@@ -88,37 +172,156 @@ pub struct Pos {pub x: f64, pub y: f64}
 
 
 impl Agent {
-	pub fn new() -> Agent {
-		let mut new_agent = Agent::with(Brain {
-			neurons_inp: arr![Neuron::new(6+OUTS)   ],
-			neurons_hid: vec![Neuron::new(6+OUTS); 6],
-			neurons_out: arr![Neuron::new(6+OUTS)   ],
-			generation: 0
-		}, Colour::new(), 40.0, 255);
+	pub fn new(cfg: &SimConfig, ids: &mut AgentIds, innov: &mut Innovations, rng: &mut SimRng) -> Agent {
+		let (input_kinds, output_kinds) = (input::layout(cfg), output::layout(cfg));
+		let outs = output_kinds.len();
+
+		let mut brain = Brain {
+			neurons_inp: input_kinds.iter().map(|_| Neuron::new(6+outs, innov, rng)).collect(),
+			neurons_hid: vec![Neuron::new(6+outs, innov, rng); 6],
+			neurons_out: (0..outs).map(|_| Neuron::new(6+outs, innov, rng)).collect(),
+			input_kinds,
+			output_kinds,
+			generation: 0,
+			in_flight: vec![],
+			clock: 0
+		};
+
+		// The hidden neurons start out as copies of one, but are unrelated genes from now on
+		brain.renumber(innov);
+
+		let mut new_agent = Agent::with(brain, Colour::new(rng), 40.0, 255, (ids.next_id(), None), cfg, rng);
 
-		for _ in 0..rand_range(0..32) {
-			new_agent = new_agent.mutate()
+		for _ in 0..rand_range(rng, 0..32) {
+			new_agent = new_agent.mutate(innov, rng)
 		}
 
+		new_agent.body.energy = metabolism::birth_energy(40.0, &cfg.metabolism);
 		new_agent
 	}
 
-	pub fn maybe_split(agents: &mut Vec<Agent>) -> Option<Agent> {
-		// TODO: consider instead spawning children of all-time high scorers
+	/// Spawns a new agent around an existing brain, e.g. one imported from a genome.
+	/// The brain shares no history with any in this world, so its genes get fresh innovation ids.
+	pub fn from_brain(mut brain: Brain, cfg: &SimConfig, ids: &mut AgentIds, innov: &mut Innovations, rng: &mut SimRng) -> Agent {
+		brain.renumber(innov);
+		brain.fit_io(cfg, innov, rng);
+
+		let mut agent = Agent::with(brain, Colour::new(rng), 40.0, 255, (ids.next_id(), None), cfg, rng);
+		agent.body.energy = metabolism::birth_energy(40.0, &cfg.metabolism);
+		agent
+	}
+
+	/// Spawns a mutated descendant of a long-dead agent, e.g. from the hall of fame
+	pub(crate) fn descendant(brain: &Brain, (colour, freq): (Colour, usize), ancestor: u64,
+		cfg: &SimConfig, ids: &mut AgentIds, innov: &mut Innovations, rng: &mut SimRng
+	) -> Agent {
+		let mut brain = brain.clone();
+		brain.generation += 1;
+		brain.fit_io(cfg, innov, rng);
+
+		let mut agent = Agent::with(brain, colour, 40.0, freq, (ids.next_id(), Some(ancestor)), cfg, rng).mutate(innov, rng);
+		agent.body.energy = metabolism::birth_energy(40.0, &cfg.metabolism);
+		agent
+	}
+
+	pub fn maybe_split(agents: &mut [Agent], cfg: &SimConfig, species: &Speciation,
+		ids: &mut AgentIds, innov: &mut Innovations, rng: &mut SimRng
+	) -> Vec<Agent> {
+		if cfg.split_output {
+			// Every agent whose brain decides to split does, as long as both halves survive it
+			// and it has not split too recently
+			let mut children = vec![];
+			for parent in agents {
+				parent.split_cooldown = parent.split_cooldown.saturating_sub(1);
+
+				let size       = parent.body.size;
+				let child_size = parent.child_ratio(cfg)*size;
+				let remaining  = (size*size - child_size*child_size).sqrt();
+
+				if parent.body.split > 0.0 && parent.split_cooldown == 0 && child_size > cfg.death_size && remaining > cfg.death_size {
+					children.push(parent.split(child_size, cfg, ids, innov, rng));
+
+					// Fitness sharing: the more crowded its species, the longer an agent waits to split again
+					parent.split_cooldown = match cfg.species.enabled && cfg.species.fitness_sharing {
+						true => (cfg.split_cooldown as f64 * species.crowding(parent.species)).round() as u64,
+						_    => cfg.split_cooldown
+					}
+				}
+			}
+
+			return children
+		}
+
 		for parent in agents {
-			if parent.body.size > 96.0 {
-				let div        = 1.0 + (parent.body.size - 96.0)/16.0;
-				let inv_chance = parent.inv_split_freq / (div as usize);
+			if parent.body.size > cfg.split_size {
+				let div        = 1.0 + (parent.body.size - cfg.split_size)/16.0;
+				let mut inv_chance = parent.inv_split_freq / (div as usize);
+
+				// Fitness sharing: the more crowded its species, the less often an agent splits
+				if cfg.species.enabled && cfg.species.fitness_sharing {
+					inv_chance = (inv_chance as f64 * species.crowding(parent.species)).round() as usize
+				}
+
+				if rand_range(rng, 0..=inv_chance) == 0 {
+					let child_size = parent.child_ratio(cfg)*parent.body.size;
+
+					return vec![parent.split(child_size, cfg, ids, innov, rng)]
+				}
+			}
+		}
+
+		vec![]
+	}
+
+	/// Lets two closely overlapping agents of similar size, both larger than
+	/// `SimConfig::mate_size`, produce a child with a crossover of their brains
+	pub fn maybe_mate(agents: &mut [Agent], grid: &mut Grid, cfg: &SimConfig,
+		ids: &mut AgentIds, innov: &mut Innovations, rng: &mut SimRng
+	) -> Option<Agent> {
+		for agent in &mut *agents {
+			agent.mate_cooldown = agent.mate_cooldown.saturating_sub(1)
+		}
+
+		if !cfg.mating {return None}
 
-				// TODO: decide when to split based on a third neuron output instead?
-				if rand_range(0..=inv_chance) == 0 {
-					// Spawn child agent
+		let ready = |agent: &Agent| agent.body.size > cfg.mate_size && agent.mate_cooldown == 0;
 
-					let child_size = 0.42*parent.body.size;
+		grid.rebuild(agents, cfg);
 
-					parent.body.remove(child_size); // shrink parent
+		let mut nearby = vec![];
+		for i in 0..agents.len() {
+			if !ready(&agents[i]) {continue}
 
-					return Some(parent.spawn_child(child_size))
+			let (pos, size) = (agents[i].body.pos, agents[i].body.size);
+
+			grid.overlapping(pos, size/2.0, &mut nearby);
+			for &j in &nearby {
+				if j <= i || !ready(&agents[j]) {continue} // consider each pair once
+
+				let (pos2, size2) = (agents[j].body.pos, agents[j].body.size);
+
+				// Neither able to eat the other
+				let similar = size <= size2*cfg.eat_ratio && size2 <= size*cfg.eat_ratio;
+
+				if similar && game::closely_overlapping(pos, pos2, size, size2, cfg) {
+					let child_size = cfg.child_ratio*size.min(size2);
+
+					// Both parents contribute half of the child
+					let mut energy = 0.0;
+					for k in [i, j] {
+						agents[k].body.remove(child_size/SQRT_2);
+						agents[k].mate_cooldown = cfg.mate_cooldown;
+						agents[k].achievements.offspring += 1;
+
+						energy += metabolism::endow(&mut agents[k].body, child_size/SQRT_2, &cfg.metabolism)
+					}
+
+					// The larger parent counts as the fitter one
+					let (fitter, other) = if size >= size2 {(&agents[i], &agents[j])} else {(&agents[j], &agents[i])};
+
+					let mut child = fitter.mate_with(other, child_size, cfg, ids, innov, rng);
+					child.body.energy = energy;
+					return Some(child)
 				}
 			}
 		}
@@ -126,54 +329,128 @@ impl Agent {
 		None
 	}
 
-	fn with(brain: Brain, colour: Colour, size: f64, freq: usize) -> Agent {
+	// Child size relative to parent size, if not evolved then fixed
+	fn child_ratio(&self, cfg: &SimConfig) -> f64 {
+		match cfg.child_ratio_output {
+			true => 0.5 + 0.4*self.body.child_ratio.clamp(-1.0, 1.0), // within [0.1, 0.9]
+			_    => cfg.child_ratio
+		}
+	}
+
+	pub(crate) fn record_kill(&mut self) {
+		self.achievements.kills += 1;
+		self.record_meal()
+	}
+
+	pub(crate) fn record_meal(&mut self) {
+		self.since_meal = 0;
+
+		// Eating is the only way to grow
+		self.achievements.max_size = self.achievements.max_size.max(self.body.size)
+	}
+
+	fn with(brain: Brain, colour: Colour, size: f64, freq: usize,
+		(id, parent): (u64, Option<u64>), cfg: &SimConfig, rng: &mut SimRng
+	) -> Agent {
 		Agent {
+			id,
+			parent,
+			mate: None,
+
+			species: None,
+
 			brain,
 
 			body: Body {
 				colour,
-				pos: Pos::new(),
+				pos: Pos::new(cfg, rng),
 				size,
-				angle: rand_range(-PI..PI),
+				angle: rand_range(rng, -PI..PI),
 
 				mov: 0.0,
-				rot: 0.0
+				rot: 0.0,
+
+				split       : 0.0,
+				child_ratio : 0.0,
+
+				energy: 0.0
 			},
 
 			alive: true,
 
-			inv_split_freq: freq
+			inv_split_freq: freq,
+
+			mate_cooldown: 0,
+
+			split_cooldown: 0,
+
+			since_meal: 0,
+
+			achievements: Achievements {max_size: size, ..Default::default()}
 		}
 	}
 
-	fn spawn_child(&self, child_size: f64) -> Agent {
+	fn split(&mut self, child_size: f64, cfg: &SimConfig, ids: &mut AgentIds, innov: &mut Innovations, rng: &mut SimRng) -> Agent {
+		self.body.remove(child_size); // shrink parent
+		self.achievements.offspring += 1;
+
+		let mut child = self.spawn_child(child_size, cfg, ids, innov, rng);
+		child.body.energy = metabolism::endow(&mut self.body, child_size, &cfg.metabolism);
+		child
+	}
+
+	fn spawn_child(&self, child_size: f64, cfg: &SimConfig, ids: &mut AgentIds, innov: &mut Innovations, rng: &mut SimRng) -> Agent {
 		let freq   = self.inv_split_freq;
 		let colour = self.body.colour.clone();
+		let family = (ids.next_id(), Some(self.id));
 
 		let mut brain = self.brain.clone();
 
 		// Spawn identical copy of self in 1/3 of cases, otherwise mutate
-		return if rand_range(0..3) == 0 {
-			Agent::with(brain, colour, child_size, freq)
+		let mut child = if rand_range(rng, 0..3) == 0 {
+			Agent::with(brain, colour, child_size, freq, family, cfg, rng)
 		} else {
 			brain.generation += 1;
-			Agent::with(brain, colour, child_size, freq).mutate()
+			Agent::with(brain, colour, child_size, freq, family, cfg, rng).mutate(innov, rng)
+		};
+
+		child.species = self.species;
+		child
+	}
+
+	fn mate_with(&self, other: &Agent, child_size: f64, cfg: &SimConfig,
+		ids: &mut AgentIds, innov: &mut Innovations, rng: &mut SimRng
+	) -> Agent {
+		let freq   = self.inv_split_freq;
+		let colour = self.body.colour.mix(&other.body.colour);
+		let family = (ids.next_id(), Some(self.id));
+
+		let brain = crossover::crossover(&self.brain, &other.brain, rng);
+
+		// Like splitting, only mutate in 2/3 of cases
+		let mut child = Agent::with(brain, colour, child_size, freq, family, cfg, rng);
+		if rand_range(rng, 0..3) != 0 {
+			child = child.mutate(innov, rng)
 		}
+
+		child.mate    = Some(other.id);
+		child.species = self.species;
+		child
 	}
 
-	fn mutate(mut self) -> Self {
-		self.brain.mutate();
-		self.body.mutate();
+	fn mutate(mut self, innov: &mut Innovations, rng: &mut SimRng) -> Self {
+		self.brain.mutate(innov, rng);
+		self.body.mutate(rng);
 
 		// Mutate inverse split frequency
-		if rand_range(0..=self.inv_split_freq) == 0 {
-			if rand_range(0..=1) == 0 || self.inv_split_freq <= 1 {
+		if rand_range(rng, 0..=self.inv_split_freq) == 0 {
+			if rand_range(rng, 0..=1) == 0 || self.inv_split_freq <= 1 {
 				self.inv_split_freq *= 2
 			} else {
 				self.inv_split_freq /= 2
 			}
 		} else {
-			self.inv_split_freq.add_bounded(rand_range(-1..=1))
+			self.inv_split_freq.add_bounded(rand_range(rng, -1..=1))
 		}
 
 		self
@@ -186,186 +463,334 @@ impl Agent {
 
 
 impl Brain {
-	pub fn input(&mut self) -> &mut [Neuron; INPS] {&mut self.neurons_inp}
+	pub fn hidden_count(&self) -> usize {self.neurons_hid.len()}
+
+	/// Checks that every input & output neuron has a kind and every connection & spike a receiver
+	pub(crate) fn validate(&self) -> Result<(), String> {
+		if self.neurons_inp.len() != self.input_kinds.len() || self.neurons_out.len() != self.output_kinds.len() {
+			return Err(format!(
+				"invalid brain: {} inputs & {} outputs, but {} input & {} output kinds",
+				self.neurons_inp.len(), self.neurons_out.len(), self.input_kinds.len(), self.output_kinds.len()
+			))
+		}
+
+		let recv_neurons = self.neurons_out.len() + self.neurons_hid.len();
+		let dest_indices = self.neurons_inp.iter().chain(&self.neurons_hid).chain(&self.neurons_out)
+			.flat_map(|neuron| neuron.next_conn.iter().map(|conn| conn.dest_index))
+			.chain(self.in_flight.iter().map(|spike| spike.dest_index));
+
+		for dest_index in dest_indices {
+			if dest_index >= recv_neurons {
+				return Err(format!("invalid brain: connection to #{dest_index} but only {recv_neurons} receiving neurons"))
+			}
+		}
+
+		Ok(())
+	}
+
+	pub fn reachable_count(&self) -> usize {
+		self.neurons_hid.iter().filter(|neuron| neuron.reachable).count()
+	}
+
+	pub fn conn_count(&self) -> usize {
+		self.neurons_inp.iter().chain(&self.neurons_hid).chain(&self.neurons_out)
+			.map(|neuron| neuron.next_conn.len())
+			.sum()
+	}
+
+	/// Gives every neuron & connection a fresh innovation id, as if they had all just evolved
+	pub(crate) fn renumber(&mut self, innov: &mut Innovations) {
+		for neuron in self.neurons_inp.iter_mut().chain(&mut self.neurons_hid).chain(&mut self.neurons_out) {
+			neuron.innovation = innov.next_id();
+
+			for conn in &mut neuron.next_conn {
+				conn.innovation = innov.next_id()
+			}
+		}
+	}
+
+	/// Advances the brain by one tick, returning how much of it was active
+	pub fn update_neurons(&mut self, cfg: &SimConfig) -> Activity {
+		self.clock += 1;
+		let mut activity = Activity::default();
+
+		// Settle output neurons from previous excitation
+		for neuron in &mut self.neurons_out {
+			let model = neuron.model(cfg);
+			let fired = model.fires(neuron);
 
-	pub fn update_neurons(&mut self) -> &[Neuron; OUTS] {
-		// Drain output neurons from previous excitation
-		for i in 0..OUTS {
-			self.neurons_out[i].drain()
+			model.settle(neuron, fired)
+		}
+
+		// Deliver spikes whose conduction delay has passed
+		for spike in &mut self.in_flight {
+			spike.ticks_left -= 1
+		}
+		for spike in self.in_flight.extract_if(.., |spike| spike.ticks_left == 0).collect::<Vec<_>>() {
+			let recv_neuron = self.recv_neuron(spike.dest_index);
+
+			recv_neuron.excitation += spike.amount;
+			recv_neuron.reachable   = true
 		}
 
 		for i in 0..self.neurons_inp.len() {
 			self.neurons_inp[i].reachable = true; // input neurons always reachable
-			self.update_neuron(i, true)
+			if self.update_neuron(i, true, cfg) {
+				activity.fired   += 1;
+				activity.signals += self.neurons_inp[i].next_conn.len()
+			}
 		}
 
 		for i in 0..self.neurons_hid.len() {
 			if self.neurons_hid[i].reachable {
-				self.update_neuron(i, false);
-				self.neurons_hid[i].drain()
+				let fired = self.update_neuron(i, false, cfg);
+				if fired {
+					activity.fired   += 1;
+					activity.signals += self.neurons_hid[i].next_conn.len()
+				}
+
+				let neuron = &mut self.neurons_hid[i];
+				neuron.model(cfg).settle(neuron, fired)
 			}
 		}
 
-		&self.neurons_out
+		for neuron in &mut self.neurons_out {
+			let model = neuron.model(cfg);
+			model.integrate(neuron);
+
+			if model.fires(neuron) {
+				neuron.last_fired = Some(self.clock);
+				activity.fired += 1
+			}
+		}
+
+		if cfg.plasticity.enabled {
+			plasticity::apply(self, &cfg.plasticity)
+		}
+
+		activity
 	}
 
-	fn update_neuron(&mut self, i: usize, is_input: bool) {
+	// Returns whether the neuron fired
+	fn update_neuron(&mut self, i: usize, is_input: bool, cfg: &SimConfig) -> bool {
 		let neuron = match is_input {
-			true => &self.neurons_inp[i],
-			_    => &self.neurons_hid[i]
+			true => &mut self.neurons_inp[i],
+			_    => &mut self.neurons_hid[i]
 		};
 
-		let excitation = neuron.excitation;
+		let model = neuron.model(cfg);
+		model.integrate(neuron);
 
 		// If neuron activated...
-		if excitation >= neuron.act_threshold {
-			// ... prepare all connections for activation
-			let mut activations = vec![];
-			for conn in &neuron.next_conn {
-				activations.push(conn.clone())
+		if !model.fires(neuron) {return false}
+
+		neuron.last_fired = Some(self.clock);
+
+		// ... prepare all its signals from its excitation before any are delivered,
+		// as it may connect to itself
+		let neuron = &*neuron;
+		let signals = neuron.next_conn.iter().map(|&conn| (conn, model.signal(neuron, &conn))).collect::<Vec<_>>();
+
+		// ... and then activate the connections
+		for (conn, amount) in signals {
+			// Delayed connections deliver their spike some ticks later instead
+			if conn.speed > 0 {
+				self.in_flight.push(Spike {dest_index: conn.dest_index, ticks_left: conn.speed, amount});
+				continue
 			}
 
-			// ... and then activate the connections
-			for conn in &mut activations {
-				let recv_neuron = if conn.dest_index < OUTS {
-					&mut self.neurons_out[conn.dest_index]
-				} else {
-					&mut self.neurons_hid[conn.dest_index - OUTS]
-				};
+			let recv_neuron = self.recv_neuron(conn.dest_index);
 
-				//let prev_recv_excitation = recv_neuron.excitation;
+			recv_neuron.excitation += amount;
+			recv_neuron.reachable   = true
+		}
 
-				if conn.relu {
-					recv_neuron.excitation += conn.weight * excitation
-				} else {
-					recv_neuron.excitation += conn.weight;
-
-					// STDP (Spike-Timing-Dependent Plasticity)
-					// TODO: maybe make more realistic?
-					/*if prev_recv_excitation >= recv_neuron.act_threshold {
-						// Receiver already has fired => weaken connection
-						if conn.weight.abs() > 1.0 {
-							Neuron::expand_or_shrink(&mut conn.weight, -1.0)
-						}
-					} else if recv_neuron.excitation >= recv_neuron.act_threshold {
-						// Receiver firing thanks to this => strengthen connection
-						if conn.weight.abs() < 8.0 {
-							Neuron::expand_or_shrink(&mut conn.weight, 1.0)
-						}
-					}*/
-				}
+		true
+	}
 
-				recv_neuron.reachable = true
-			}
+	/// Rearranges input & output neurons to match the sensors & actuators enabled by the config,
+	/// keeping the neurons of those the brain already had, and renumbering connections accordingly
+	pub(crate) fn fit_io(&mut self, cfg: &SimConfig, innov: &mut Innovations, rng: &mut SimRng) {
+		let (input_kinds, output_kinds) = (input::layout(cfg), output::layout(cfg));
+		if input_kinds == self.input_kinds && output_kinds == self.output_kinds {return}
 
-			// ... and finally apply potential STDP changes
-			match is_input {
-				true => self.neurons_inp[i].next_conn = activations,
-				_    => self.neurons_hid[i].next_conn = activations
+		self.in_flight.clear();
+
+		// Old receiving neuron index -> new one, if still present
+		let (outs0, outs) = (self.output_kinds.len(), output_kinds.len());
+		let new_out: Vec<_> = self.output_kinds.iter().map(|kind| output_kinds.iter().position(|k| k == kind)).collect();
+		let renumber = |dest: usize| if dest < outs0 {new_out[dest]} else {Some(dest - outs0 + outs)};
+
+		for neuron in self.neurons_inp.iter_mut().chain(&mut self.neurons_hid).chain(&mut self.neurons_out) {
+			neuron.next_conn.retain_mut(|conn| match renumber(conn.dest_index) {
+				Some(dest) => {conn.dest_index = dest; true}
+				None       => false
+			})
+		}
+
+		let recv_neurons = self.neurons_hid.len() + outs;
+
+		self.neurons_inp = Brain::refit(std::mem::take(&mut self.neurons_inp), &self.input_kinds, &input_kinds, recv_neurons, innov, rng);
+		self.neurons_out = Brain::refit(std::mem::take(&mut self.neurons_out), &self.output_kinds, &output_kinds, recv_neurons, innov, rng);
+
+		self.input_kinds  = input_kinds;
+		self.output_kinds = output_kinds;
+
+		// Ensure input & output neurons keep at least one outgoing connection
+		for neuron in self.neurons_inp.iter_mut().chain(&mut self.neurons_out) {
+			if neuron.next_conn.is_empty() {
+				neuron.next_conn.push(OutwardConn::new(recv_neurons, innov, rng))
 			}
 		}
 	}
 
-	fn mutate(&mut self) {
-		let mut recv_neurons = self.neurons_hid.len() + OUTS;
+	// Reorders neurons from `kinds0` to `kinds`, adding fresh ones for new kinds
+	fn refit<K: PartialEq>(neurons: Vec<Neuron>, kinds0: &[K], kinds: &[K], recv_neurons: usize,
+		innov: &mut Innovations, rng: &mut SimRng
+	) -> Vec<Neuron> {
+		let mut neurons: Vec<_> = neurons.into_iter().map(Some).collect();
+
+		kinds.iter().map(|kind| match kinds0.iter().position(|k| k == kind) {
+			Some(i) => neurons[i].take().expect("kinds are unique"),
+			None    => Neuron::new(recv_neurons, innov, rng)
+		}).collect()
+	}
+
+	fn recv_neuron(&mut self, dest_index: usize) -> &mut Neuron {
+		let outs = self.neurons_out.len();
+
+		if dest_index < outs {
+			&mut self.neurons_out[dest_index]
+		} else {
+			&mut self.neurons_hid[dest_index - outs]
+		}
+	}
+
+	fn mutate(&mut self, innov: &mut Innovations, rng: &mut SimRng) {
+		// Drop spikes still in flight, as the connections they came from may be gone
+		self.in_flight.clear();
+
+		let (inps, outs) = (self.neurons_inp.len(), self.neurons_out.len());
+
+		let mut recv_neurons = self.neurons_hid.len() + outs;
 		let mut new_neurons  = 0;
 		let mut new_conns    = 0;
 
 		// Mutate input neurons
 		for neuron in &mut self.neurons_inp {
-			neuron.mutate(&mut new_neurons, &mut new_conns, recv_neurons);
+			neuron.mutate(&mut new_neurons, &mut new_conns, recv_neurons, innov, rng);
 
 			// Ensure there is always at least one outgoing connection left
-			if neuron.next_conn.len() < 1 {
-				neuron.next_conn.push(OutwardConn::new(recv_neurons))
+			if neuron.next_conn.is_empty() {
+				neuron.next_conn.push(OutwardConn::new(recv_neurons, innov, rng))
 			}
 		}
 
 		// Mutate hidden neurons
 		for neuron in &mut self.neurons_hid {
-			neuron.mutate(&mut new_neurons, &mut new_conns, recv_neurons)
+			neuron.mutate(&mut new_neurons, &mut new_conns, recv_neurons, innov, rng)
 		}
 
 		// Mutate output neurons
 		for neuron in &mut self.neurons_out {
-			neuron.mutate(&mut new_neurons, &mut new_conns, recv_neurons);
+			neuron.mutate(&mut new_neurons, &mut new_conns, recv_neurons, innov, rng);
 
 			// Ensure there is always at least one outgoing connection left
-			if neuron.next_conn.len() < 1 {
-				neuron.next_conn.push(OutwardConn::new(recv_neurons))
+			if neuron.next_conn.is_empty() {
+				neuron.next_conn.push(OutwardConn::new(recv_neurons, innov, rng))
 			}
 		}
 
 		// Add new hidden neurons
 		for _ in 0..new_neurons {
-			self.neurons_hid.push(Neuron::new(recv_neurons));
+			self.neurons_hid.push(Neuron::new(recv_neurons, innov, rng));
 			recv_neurons += 1
 		}
 
 		// Add new outgoing connections
 		for _ in 0..new_conns {
 			let hids = self.neurons_hid.len();
-			let rand = rand_range(0..INPS+hids+OUTS);
+			let rand = rand_range(rng, 0..inps+hids+outs);
 
-			let neuron = if rand < INPS {
+			let neuron = if rand < inps {
 				&mut self.neurons_inp[rand]
-			} else if rand < INPS+hids {
-				&mut self.neurons_hid[rand-INPS]
+			} else if rand < inps+hids {
+				&mut self.neurons_hid[rand-inps]
 			} else {
-				&mut self.neurons_out[rand-INPS-hids]
+				&mut self.neurons_out[rand-inps-hids]
 			};
 
-			neuron.next_conn.push(OutwardConn::new(recv_neurons))
+			neuron.next_conn.push(OutwardConn::new(recv_neurons, innov, rng))
 		}
 	}
 }
 
 impl Neuron {
-	fn new(recv_neuron_count: usize) -> Neuron {
+	fn new(recv_neuron_count: usize, innov: &mut Innovations, rng: &mut SimRng) -> Neuron {
 		Neuron {
 			excitation: 0.0,
 			tick_drain: 1.0,
 
 			act_threshold: 0.0,
 
-			next_conn: vec![OutwardConn::new(recv_neuron_count)],
+			next_conn: vec![OutwardConn::new(recv_neuron_count, innov, rng)],
+
+			innovation: innov.next_id(),
+
+			learn_rate: 0.0,
+
+			model: None,
+			state: [0.0; 2],
 
 			reachable: false,
 
+			last_fired: None,
+
 			inv_mut: 2
 		}
 	}
 
 	// By default 11/89 if mutation of mutation rate or not
-	fn should_mutate_mut(inv_mut: usize) -> bool {rand_range(0..=inv_mut.pow(3)) == 0}
+	fn should_mutate_mut(inv_mut: usize, rng: &mut SimRng) -> bool {rand_range(rng, 0..=inv_mut.pow(3)) == 0}
 	// By default 33/67 if mutation or not
-	fn should_mutate_now(inv_mut: usize) -> bool {rand_range(0..=inv_mut) == 0}
+	fn should_mutate_now(inv_mut: usize, rng: &mut SimRng) -> bool {rand_range(rng, 0..=inv_mut) == 0}
+	// By default 11/89 if mutation of conduction delay or not
+	fn should_mutate_delay(inv_mut: usize, rng: &mut SimRng) -> bool {rand_range(rng, 0..=inv_mut.pow(3)) == 0}
+	// By default 1/17 if mutation of neuron model or not
+	fn should_mutate_model(inv_mut: usize, rng: &mut SimRng) -> bool {rand_range(rng, 0..=inv_mut.pow(4)) == 0}
 	// Always 50/50 if expansion or shrinking
-	fn should_expand_now() -> bool {rand_range(0..=1) == 0}
+	fn should_expand_now(rng: &mut SimRng) -> bool {rand_range(rng, 0..=1) == 0}
 
 	fn mutate(&mut self,
 		new_neuron_count  : &mut usize,
 		new_conn_count    : &mut usize,
-		recv_neuron_count :      usize
+		recv_neuron_count :      usize,
+		innov             : &mut Innovations,
+		rng               : &mut SimRng
 	) {
 		// Mutate neuron properties
-		if Neuron::should_mutate_mut(self.inv_mut) {
-			self.inv_mut.add_bounded([-1, 1][rand_range(0..=1)])}
-		if Neuron::should_mutate_now(self.inv_mut) {
-			self.tick_drain += [-1.0, 1.0][rand_range(0..=1)]}
-		if Neuron::should_mutate_now(self.inv_mut) {
-			self.act_threshold += [-1.0, 1.0][rand_range(0..=1)]}
+		if Neuron::should_mutate_mut(self.inv_mut, rng) {
+			self.inv_mut.add_bounded([-1, 1][rand_range(rng, 0..=1)])}
+		if Neuron::should_mutate_now(self.inv_mut, rng) {
+			self.tick_drain += [-1.0, 1.0][rand_range(rng, 0..=1)]}
+		if Neuron::should_mutate_now(self.inv_mut, rng) {
+			self.act_threshold += [-1.0, 1.0][rand_range(rng, 0..=1)]}
+		if Neuron::should_mutate_now(self.inv_mut, rng) {
+			self.learn_rate = (self.learn_rate + [-0.125, 0.125][rand_range(rng, 0..=1)]).clamp(0.0, 1.0)}
+		if Neuron::should_mutate_model(self.inv_mut, rng) {
+			// Only expressed if `SimConfig::evolve_neuron_model` is set
+			self.model = Some(ModelKind::ALL[rand_range(rng, 0..ModelKind::ALL.len())])}
 
 		// Mutate outgoing connections
 		for conn in &mut self.next_conn {
-			if Neuron::should_mutate_now(self.inv_mut) {
-				if rand_range(0..(2 + conn.weight.abs() as usize)) == 0 {
+			if Neuron::should_mutate_now(self.inv_mut, rng) {
+				if rand_range(rng, 0..(2 + conn.weight.abs() as usize)) == 0 {
 					// Sometimes flip weight
 					conn.weight = -conn.weight
 				} else {
-					if Neuron::should_expand_now() {
+					if Neuron::should_expand_now(rng) {
 						// Sometimes expand weight or other stuff
-						match rand_range(0..3) {
+						match rand_range(rng, 0..3) {
 							0 => Neuron::expand_or_shrink(&mut conn.weight, 1.0),
 							1 => *new_conn_count += 1,
 							_ => *new_neuron_count += 1
@@ -376,25 +801,40 @@ impl Neuron {
 					}
 				}
 			}
+
+			// Sometimes slow down or speed up the connection
+			if Neuron::should_mutate_delay(self.inv_mut, rng) {
+				conn.speed.add_bounded_max(rand_range(rng, -1..=1), MAX_DELAY)
+			}
 		}
 
 		// Remove effectively dead connections
 		self.next_conn.retain(|conn| (conn.weight*10.0).round() != 0.0);
 
 		// If this neuron is inactive, try recycling it
-		if self.next_conn.len() < 1 && *new_neuron_count > 0 {
+		if self.next_conn.is_empty() && *new_neuron_count > 0 {
 			*new_neuron_count -= 1;
-			self.next_conn.push(OutwardConn::new(recv_neuron_count))
+			self.next_conn.push(OutwardConn::new(recv_neuron_count, innov, rng))
 		}
 
 		// Reset excitation
 		self.excitation = 0.0;
+		self.state      = [0.0; 2];
+		self.last_fired = None;
 
 		// Assume not reachable until proven otherwise
 		self.reachable = false
 	}
 
-	fn drain(&mut self) {
+	/// Firing rule of this neuron under the given config
+	pub fn model(&self, cfg: &SimConfig) -> &'static dyn NeuronModel {
+		match self.model {
+			Some(model) if cfg.evolve_neuron_model => model.model(),
+			_ => cfg.neuron_model.model()
+		}
+	}
+
+	pub(crate) fn drain(&mut self) {
 		Neuron::expand_or_shrink(&mut self.excitation, -self.tick_drain.abs())
 	}
 
@@ -415,12 +855,14 @@ impl Neuron {
 }
 
 impl OutwardConn {
-	fn new(recv_neuron_count: usize) -> OutwardConn {
+	fn new(recv_neuron_count: usize, innov: &mut Innovations, rng: &mut SimRng) -> OutwardConn {
 		OutwardConn {
-			dest_index: rand_range(0..recv_neuron_count),
+			dest_index: rand_range(rng, 0..recv_neuron_count),
 			speed: 0,
-			weight: [-1.0, 1.0][rand_range(0..=1)],
-			relu: [false, true][rand_range(0..=1)]
+			weight: [-1.0, 1.0][rand_range(rng, 0..=1)],
+			relu: [false, true][rand_range(rng, 0..=1)],
+
+			innovation: innov.next_id()
 		}
 	}
 }
@@ -439,10 +881,10 @@ impl fmt::Debug for Brain {
 		s += "\t],\n\n\tneurons_hid: [\n";
 		for (i, neuron) in self.neurons_hid.iter().enumerate() {
 			if neuron.reachable {
-				s += &format!("\t\t#{}: {neuron:#?},\n", i + OUTS)
+				s += &format!("\t\t#{}: {neuron:#?},\n", i + self.neurons_out.len())
 			} else {
 				unreachables += 1;
-				if neuron.next_conn.len() < 1 {
+				if neuron.next_conn.is_empty() {
 					inactives += 1
 				}
 			}
@@ -462,12 +904,12 @@ impl fmt::Debug for Neuron {
 	// Print neuron debug info in a concise way
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		if !self.reachable {
-			if self.next_conn.len() < 1 {
+			if self.next_conn.is_empty() {
 				write!(f, "➖ Neuron {{UNREACHABLE & INACTIVE}}")
 			} else {
 				write!(f, "➖ Neuron {{UNREACHABLE, conns={}}}", self.next_conn.len())
 			}
-		} else if self.next_conn.len() < 1 {
+		} else if self.next_conn.is_empty() {
 			write!(f, "➖ Neuron {{INACTIVE}}")
 		} else {
 			let (is_at, act_at) = (self.excitation, self.act_threshold);
@@ -489,12 +931,20 @@ impl fmt::Debug for Neuron {
 				             is_at,     act_at,     self.inv_mut
 			);
 
+			if self.learn_rate > 0.0 {
+				s += &format!("LR@{:.3} | ", self.learn_rate)
+			}
+			if let Some(model) = self.model {
+				s += &format!("MODEL@{model:?} | ")
+			}
+
 			let mut conn_iter = self.next_conn.iter().peekable();
 			while let Some(conn) = conn_iter.next() {
-				let relu = if conn.relu {"*"} else {""};
+				let relu  = if conn.relu {"*"} else {""};
+				let delay = if conn.speed > 0 {format!("~{}", conn.speed)} else {String::new()};
 
-				s += &format!("({relu}{:.1})->#{}", conn.weight, conn.dest_index);
-				if !conn_iter.peek().is_none() {
+				s += &format!("({relu}{:.1}){delay}->#{}", conn.weight, conn.dest_index);
+				if conn_iter.peek().is_some() {
 					s += ", "
 				}
 			}
@@ -510,31 +960,182 @@ impl fmt::Debug for Neuron {
 
 
 impl Body {
-	fn mutate(&mut self) {
+	fn mutate(&mut self, rng: &mut SimRng) {
 		// Slightly mutate colours
-		self.colour.r.add_bounded_max(rand_range(-16..16), 256);
-		self.colour.g.add_bounded_max(rand_range(-16..16), 256);
-		self.colour.b.add_bounded_max(rand_range(-16..16), 256);
+		self.colour.r.add_bounded_max(rand_range(rng, -16..16), 256);
+		self.colour.g.add_bounded_max(rand_range(rng, -16..16), 256);
+		self.colour.b.add_bounded_max(rand_range(rng, -16..16), 256);
 	}
 
-	fn remove(&mut self, removal: f64) {
-		let new_size = (self.size*self.size - removal*removal).sqrt();
+	pub fn radius(&self) -> f64 {self.size/2.0}
 
-		self.pos.x += (self.size - new_size)/2.0;
-		self.pos.y += (self.size - new_size)/2.0;
-
-		self.size = new_size;
+	pub(crate) fn remove(&mut self, removal: f64) {
+		self.size = (self.size*self.size - removal*removal).sqrt()
 	}
 }
 
 impl Colour {
-	fn new() -> Colour {
-		Colour {r: rand_range(0..256), g: rand_range(0..256), b: rand_range(0..256)}
+	fn new(rng: &mut SimRng) -> Colour {
+		Colour {r: rand_range(rng, 0..256), g: rand_range(rng, 0..256), b: rand_range(rng, 0..256)}
+	}
+
+	fn mix(&self, other: &Colour) -> Colour {
+		Colour {r: (self.r + other.r)/2, g: (self.g + other.g)/2, b: (self.b + other.b)/2}
 	}
 }
 
 impl Pos {
-	fn new() -> Pos {
-		Pos {x: rand_range(0.0..450.0), y: rand_range(0.0..450.0)}
+	/// Anywhere in the game area
+	pub(crate) fn new(cfg: &SimConfig, rng: &mut SimRng) -> Pos {
+		Pos {x: rand_range(rng, 0.0..cfg.width), y: rand_range(rng, 0.0..cfg.height)}
+	}
+
+	/// Shortest vector from here to `other`, across the edges if the world wraps around
+	pub fn delta(self, other: Pos, cfg: &SimConfig) -> (f64, f64) {
+		let (mut dx, mut dy) = (other.x - self.x, other.y - self.y);
+
+		if cfg.wrap {
+			dx -= cfg.width  * (dx / cfg.width).round();
+			dy -= cfg.height * (dy / cfg.height).round();
+		}
+
+		(dx, dy)
+	}
+
+	pub fn dist(self, other: Pos, cfg: &SimConfig) -> f64 {
+		let (dx, dy) = self.delta(other, cfg);
+		libm::hypot(dx, dy)
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use rand::SeedableRng;
+
+	use super::*;
+
+	pub(crate) fn neuron(next_conn: Vec<OutwardConn>) -> Neuron {
+		Neuron {
+			excitation: 0.0,
+			tick_drain: 0.0,
+			act_threshold: 0.0,
+			next_conn,
+			innovation: 0,
+			learn_rate: 0.0,
+			model: None,
+			state: [0.0; 2],
+			reachable: false,
+			last_fired: None,
+			inv_mut: 0
+		}
+	}
+
+	pub(crate) fn conn(dest_index: usize, speed: usize) -> OutwardConn {
+		OutwardConn {dest_index, speed, weight: 1.0, relu: true, innovation: 0}
+	}
+
+	pub(crate) fn brain(neurons_inp: Vec<Neuron>, neurons_hid: Vec<Neuron>, output_kinds: Vec<Output>) -> Brain {
+		Brain {
+			input_kinds: Input::LEGACY.iter().copied().take(neurons_inp.len()).collect(),
+			neurons_inp,
+			neurons_hid,
+			neurons_out: output_kinds.iter().map(|_| neuron(vec![])).collect(),
+			output_kinds,
+			generation: 0,
+			in_flight: vec![],
+			clock: 0
+		}
+	}
+
+	#[test]
+	fn signals_are_computed_before_any_is_delivered() {
+		// A hidden neuron exciting itself before the only output
+		let mut hidden = neuron(vec![conn(1, 0), conn(0, 0)]);
+		hidden.excitation = 2.0;
+		hidden.reachable  = true;
+
+		let mut brain = brain(vec![], vec![hidden], vec![Output::Mov]);
+		brain.update_neurons(&SimConfig::default());
+
+		assert_eq!(brain.neurons_out[0].excitation, 2.0)
+	}
+
+	#[test]
+	fn delayed_connections_deliver_exactly_speed_ticks_later() {
+		for speed in 0..5 {
+			let mut sender = neuron(vec![conn(0, speed)]);
+			sender.act_threshold = 0.5;
+			sender.excitation    = 1.0;
+
+			let mut brain = brain(vec![sender], vec![], vec![Output::Mov]);
+			let cfg = SimConfig::default();
+
+			// Only fires on the first tick
+			brain.update_neurons(&cfg);
+			brain.neurons_inp[0].excitation = 0.0;
+
+			let mut received = vec![];
+			for tick in 1..10 {
+				if brain.neurons_out[0].excitation != 0.0 {
+					received.push(tick)
+				}
+				brain.update_neurons(&cfg);
+			}
+			assert_eq!(received.first(), Some(&(1 + speed)), "speed {speed}")
+		}
+	}
+
+	#[test]
+	fn mutating_or_refitting_drops_spikes_in_flight() {
+		let mut sender = neuron(vec![conn(0, 3)]);
+		sender.excitation = 1.0;
+
+		let mut brain = brain(vec![sender], vec![], vec![Output::Mov]);
+		brain.update_neurons(&SimConfig::default());
+		assert_eq!(brain.in_flight.len(), 1);
+
+		let (mut innov, mut rng) = (Innovations::default(), SimRng::seed_from_u64(0));
+
+		let mut mutated = brain.clone();
+		mutated.mutate(&mut innov, &mut rng);
+		assert!(mutated.in_flight.is_empty());
+
+		// From a single input to those of the default config
+		brain.fit_io(&SimConfig::default(), &mut innov, &mut rng);
+		assert!(brain.in_flight.is_empty())
+	}
+
+	#[test]
+	fn fit_io_keeps_connections_on_their_receivers() {
+		// Every connection is tagged with the innovation id of the neuron it leads to
+		let tags = [100, 101, 102, 103, 200, 201]; // outputs, then hidden
+		let conns = || tags.iter().enumerate().map(|(dest, &tag)| OutwardConn {innovation: tag, ..conn(dest, 0)}).collect::<Vec<_>>();
+		let tagged = |tag| Neuron {innovation: tag, ..neuron(conns())};
+
+		let cfg    = SimConfig::default();
+		let inputs = input::layout(&cfg).iter().map(|_| neuron(conns())).collect();
+
+		let mut brain = brain(inputs, vec![tagged(200), tagged(201)], vec![Output::ChildRatio, Output::Split, Output::Rot, Output::Mov]);
+		for (neuron, tag) in brain.neurons_out.iter_mut().zip(100..) {
+			*neuron = tagged(tag)
+		}
+
+		brain.fit_io(&cfg, &mut Innovations::default(), &mut SimRng::seed_from_u64(0));
+
+		// Mov & Rot swap places, Split & ChildRatio are gone, and hidden neurons move up
+		assert_eq!(brain.output_kinds, [Output::Mov, Output::Rot]);
+		let recv: Vec<_> = (0..4).map(|dest| brain.recv_neuron(dest).innovation).collect();
+		assert_eq!(recv, [103, 102, 200, 201]);
+
+		let senders: Vec<_> = brain.neurons_inp.iter().chain(&brain.neurons_hid).chain(&brain.neurons_out).cloned().collect();
+		for sender in senders {
+			let mut tags: Vec<_> = sender.next_conn.iter().map(|conn| conn.innovation).collect();
+			tags.sort();
+			assert_eq!(tags, [102, 103, 200, 201]);
+
+			for conn in &sender.next_conn {
+				assert_eq!(brain.recv_neuron(conn.dest_index).innovation, conn.innovation)
+			}
+		}
 	}
 }
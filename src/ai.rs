@@ -1,22 +1,34 @@
-use crate::{agent::*, input, output};
+use crate::{agent::*, config::SimConfig, food::Food, input, metabolism, output, perception::Percept, spatial::Grid, vision::Vision};
+
+pub fn update_ai(agents: &mut [Agent], grid: &mut Grid, food: &Food, cfg: &SimConfig) {
+	grid.rebuild(agents, cfg);
 
-pub fn update_ai(agents: &mut Vec<Agent>) {
 	for i in 0..agents.len() {
-		if agents[i].body.size < 32.0 {
-			continue // for performance reasons, small agents are just stationary food
+		if agents[i].body.size < cfg.food_size {
+			continue // optionally, small agents are just stationary food
 		}
 
-		let nearest = input::Nearest::to(agents, i);
-		let agent   = &mut agents[i];
+		let senses = input::Senses {
+			nearest : input::Nearest::to(agents, i, grid, cfg),
+			percept : Percept::of(agents, i, grid, cfg),
+			vision  : Vision::of(agents, i, grid, cfg),
+			food    : match cfg.food.enabled && cfg.food.sense {
+				true => food.scent(&agents[i].body, cfg),
+				_    => Default::default()
+			}
+		};
+		let agent = &mut agents[i];
 
 		// Input
-		input::assign(agent.brain.input(), &agent.body, nearest);
+		input::assign(agent, &senses, cfg);
 
 		// Input -> ... -> Output
-		let output = agent.brain.update_neurons();
+		let activity = agent.brain.update_neurons(cfg);
+		if cfg.metabolism.enabled {
+			metabolism::think(&mut agent.body, activity, &cfg.metabolism)
+		}
 
 		// Output
-		output::assign(&mut agent.body.mov, &output[0]);
-		output::assign(&mut agent.body.rot, &output[1]);
+		output::assign_all(&mut agent.body, &agent.brain, cfg);
 	}
 }
@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{food::FoodConfig, hall_of_fame::HallOfFameConfig, input::Own, neuron_model::ModelKind, perception::PerceptionConfig};
+use crate::{metabolism::MetabolismConfig, plasticity::PlasticityConfig, species::SpeciesConfig, vision::VisionConfig};
+
+/// Every tunable constant of the simulation, loadable from TOML or JSON.
+/// Missing fields fall back to their defaults.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimConfig {
+	pub width  : f64,
+	pub height : f64,
+	pub wrap   : bool, // toroidal world, where agents leaving one edge come back in at the opposite one
+
+	pub mov_speed: f64, // distance moved per tick at full speed
+	pub rot_speed: f64, // fraction of PI turned per tick at full speed
+
+	pub inverse_spawn_rate: usize,
+
+	pub food_size: f64, // agents smaller than this are stationary food, 0 to let every agent think
+
+	pub grid_cell_size: f64, // cell size of the spatial grid used for collisions & perception
+
+	pub split_size  : f64, // agents larger than this may split
+	pub child_ratio : f64, // child size relative to (smaller) parent size
+
+	pub split_output       : bool, // let the brain decide when to split instead of the size & dice roll
+	pub child_ratio_output : bool, // let the brain decide the child size ratio when splitting
+	pub split_cooldown     : u64,  // with `split_output`, ticks before an agent may split again
+
+	pub mating        : bool, // sexual reproduction of overlapping agents of similar size
+	pub mate_size     : f64,  // agents larger than this may mate
+	pub mate_cooldown : u64,  // ticks before an agent may mate again
+
+	pub shrink_factor : f64, // size multiplier per tick when idle
+	pub eat_ratio     : f64, // how much larger an agent must be to eat another
+	pub death_size    : f64, // agents smaller than this starve to death
+
+	pub stats_interval: u64, // ticks between population statistics samples, 0 to disable
+
+	pub proprioception: Vec<Own>, // which of its own state an agent senses, in input order
+
+	pub neuron_model        : ModelKind, // firing rule of every neuron
+	pub evolve_neuron_model : bool,      // let neurons evolve their own firing rule instead
+
+	pub metabolism   : MetabolismConfig,
+	pub food         : FoodConfig,
+	pub perception   : PerceptionConfig,
+	pub vision       : VisionConfig,
+	pub plasticity   : PlasticityConfig,
+	pub species      : SpeciesConfig,
+	pub hall_of_fame : HallOfFameConfig
+}
+
+impl Default for SimConfig {
+	fn default() -> Self {
+		SimConfig {
+			width  : 600.0,
+			height : 600.0,
+			wrap   : false,
+
+			mov_speed: 2.0,
+			rot_speed: 0.1,
+
+			inverse_spawn_rate: 32,
+
+			food_size: 32.0,
+
+			grid_cell_size: 64.0,
+
+			split_size  : 96.0,
+			child_ratio : 0.42,
+
+			split_output       : false,
+			child_ratio_output : false,
+			split_cooldown     : 100,
+
+			mating        : false,
+			mate_size     : 64.0,
+			mate_cooldown : 200,
+
+			shrink_factor : 0.9999,
+			eat_ratio     : 1.1,
+			death_size    : 4.0,
+
+			stats_interval: 100,
+
+			proprioception: vec![],
+
+			neuron_model        : ModelKind::Threshold,
+			evolve_neuron_model : false,
+
+			metabolism   : MetabolismConfig::default(),
+			food         : FoodConfig::default(),
+			perception   : PerceptionConfig::default(),
+			vision       : VisionConfig::default(),
+			plasticity   : PlasticityConfig::default(),
+			species      : SpeciesConfig::default(),
+			hall_of_fame : HallOfFameConfig::default()
+		}
+	}
+}
+
+impl SimConfig {
+	pub fn from_json(s: &str) -> Result<Self, String> {
+		SimConfig::from_value(serde_json::from_str(s).map_err(|e| format!("invalid config: {e}"))?)
+	}
+
+	pub fn from_toml(s: &str) -> Result<Self, String> {
+		SimConfig::from_value(toml::from_str(s).map_err(|e| format!("invalid config: {e}"))?)
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).expect("config is always serialisable")
+	}
+
+	pub fn to_toml(&self) -> String {
+		toml::to_string(self).expect("config is always serialisable")
+	}
+
+	fn from_value(mut value: Value) -> Result<Self, String> {
+		SimConfig::upgrade(&mut value);
+		serde_json::from_value::<Self>(value).map_err(|e| format!("invalid config: {e}"))?.validated()
+	}
+
+	/// Rewrites fields of older configs, from when worlds were always square:
+	/// `game_size` stands for both `width` & `height`
+	pub(crate) fn upgrade(value: &mut Value) {
+		let Some(object) = value.as_object_mut() else {return};
+
+		if let Some(size) = object.remove("game_size") {
+			for key in ["width", "height"] {
+				object.entry(key).or_insert_with(|| size.clone());
+			}
+		}
+	}
+
+	pub(crate) fn validated(self) -> Result<Self, String> {
+		if self.inverse_spawn_rate == 0 {
+			return Err("invalid config: inverse_spawn_rate must be at least 1".into())
+		}
+
+		if self.species.interval == 0 {
+			return Err("invalid config: species.interval must be at least 1".into())
+		}
+
+		if !(0.0..=1.0).contains(&self.hall_of_fame.spawn_ratio) {
+			return Err("invalid config: hall_of_fame.spawn_ratio must be between 0 and 1".into())
+		}
+
+		if self.width <= 0.0 || self.height <= 0.0 || self.split_size <= 0.0 || self.grid_cell_size <= 0.0 {
+			return Err("invalid config: width, height, split_size and grid_cell_size must be positive".into())
+		}
+
+		if !(0.0 < self.child_ratio && self.child_ratio < 1.0 && 0.0 < self.shrink_factor && self.shrink_factor <= 1.0) {
+			return Err("invalid config: child_ratio must be between 0 and 1, shrink_factor above 0 and at most 1".into())
+		}
+
+		if !(self.eat_ratio >= 1.0 && self.death_size >= 0.0 && self.mov_speed >= 0.0) {
+			return Err("invalid config: eat_ratio must be at least 1, death_size and mov_speed not negative".into())
+		}
+
+		let own = &self.proprioception;
+		if own.iter().enumerate().any(|(i, kind)| own[..i].contains(kind)) {
+			return Err("invalid config: proprioception must not list the same input twice".into())
+		}
+
+		// Burning the body pays `growth_cost` per unit of area, so at 0 nothing could ever starve
+		if !(self.metabolism.reserve >= 0.0 && self.metabolism.growth_cost > 0.0) {
+			return Err("invalid config: metabolism.reserve must not be negative and metabolism.growth_cost must be positive".into())
+		}
+
+		if self.food.range <= 0.0 || self.food.regrowth < 0.0 || self.food.patch_radius < 0.0 {
+			return Err("invalid config: food.range must be positive, food.regrowth and food.patch_radius not negative".into())
+		}
+
+		let plasticity = &self.plasticity;
+		if !(0.0 <= plasticity.min_weight && plasticity.min_weight <= plasticity.max_weight) {
+			return Err("invalid config: plasticity.min_weight must be between 0 and plasticity.max_weight".into())
+		}
+
+		if plasticity.tau <= 0.0 || plasticity.window == 0 {
+			return Err("invalid config: plasticity.tau and plasticity.window must be positive".into())
+		}
+
+		if self.perception.range <= 0.0 || self.vision.range <= 0.0 {
+			return Err("invalid config: perception.range and vision.range must be positive".into())
+		}
+
+		Ok(self)
+	}
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::*, helpers::*};
+
+/// World-wide counter handing out NEAT's innovation numbers: every neuron & connection gets a
+/// fresh one when it evolves and keeps it when inherited, so genes sharing an id share an origin.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Innovations {
+	next: u64
+}
+
+impl Innovations {
+	pub fn next_id(&mut self) -> u64 {
+		self.next += 1;
+		self.next
+	}
+}
+
+/// NEAT-style crossover of two brains. Input & output neurons line up by position, hidden
+/// neurons and connections by innovation id: genes present in both parents are inherited from
+/// either at random, disjoint & excess genes only from the fitter parent.
+pub fn crossover(fitter: &Brain, other: &Brain, rng: &mut SimRng) -> Brain {
+	let mut child = fitter.clone();
+
+	for (neuron, other) in child.neurons_inp.iter_mut().zip(&other.neurons_inp) {
+		cross(neuron, other, rng)
+	}
+	for neuron in &mut child.neurons_hid {
+		match other.neurons_hid.iter().find(|hid| hid.innovation == neuron.innovation) {
+			Some(other) => cross(neuron, other, rng),
+			None        => reset(neuron)
+		}
+	}
+	for (neuron, other) in child.neurons_out.iter_mut().zip(&other.neurons_out) {
+		cross(neuron, other, rng)
+	}
+
+	child.generation = fitter.generation.max(other.generation) + 1;
+	child.in_flight.clear();
+	child.clock = 0;
+
+	child
+}
+
+fn cross(neuron: &mut Neuron, other: &Neuron, rng: &mut SimRng) {
+	let mut either = || rand_range(rng, 0..=1) == 0;
+
+	if either() {neuron.tick_drain    = other.tick_drain}
+	if either() {neuron.act_threshold = other.act_threshold}
+	if either() {neuron.inv_mut       = other.inv_mut}
+	if either() {neuron.learn_rate    = other.learn_rate}
+	if either() {neuron.model         = other.model}
+
+	// Matching connections come from either parent, keeping the destination's index in this brain
+	let pairs = pair_conns(neuron, other).into_iter().enumerate().filter_map(|(k, pair)| Some((k, pair?)));
+	for (k, pair) in pairs {
+		if either() {
+			neuron.next_conn[k] = OutwardConn {dest_index: neuron.next_conn[k].dest_index, ..other.next_conn[pair]}
+		}
+	}
+
+	reset(neuron)
+}
+
+fn reset(neuron: &mut Neuron) {
+	neuron.excitation = 0.0;
+	neuron.state      = [0.0; 2];
+	neuron.last_fired = None;
+	neuron.reachable  = false
+}
+
+/// For each connection of `neuron`, the connection of `other` with the same innovation id, if any
+fn pair_conns(neuron: &Neuron, other: &Neuron) -> Vec<Option<usize>> {
+	neuron.next_conn.iter()
+		.map(|conn| other.next_conn.iter().position(|o| o.innovation == conn.innovation))
+		.collect()
+}
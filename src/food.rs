@@ -0,0 +1,247 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::*, config::SimConfig, game, helpers::*, spatial::Grid};
+
+/// Plant-like pellets growing on their own, giving agents something to forage besides each other
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FoodConfig {
+	pub enabled: bool,
+
+	pub max_pellets : usize, // no more grow while this many are lying around
+	pub regrowth    : f64,   // pellets grown per tick on average
+	pub pellet_size : f64,   // eaten like an agent of this size
+
+	pub distribution : Distribution,
+	pub patches      : usize, // number of patches for `Distribution::Patches`
+	pub patch_radius : f64,
+
+	pub sense : bool, // whether agents sense the nearest pellet
+	pub range : f64   // how far agents can sense pellets, centre to centre
+}
+
+impl Default for FoodConfig {
+	fn default() -> Self {
+		FoodConfig {
+			enabled: false,
+
+			max_pellets : 200,
+			regrowth    : 0.5,
+			pellet_size : 8.0,
+
+			distribution : Distribution::Uniform,
+			patches      : 4,
+			patch_radius : 60.0,
+
+			sense : true,
+			range : 200.0
+		}
+	}
+}
+
+/// Where new pellets grow
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Distribution {
+	Uniform, // anywhere
+	Patches, // within a few fertile patches, placed randomly once
+	Centre   // more densely towards the middle
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Pellet {
+	pub pos  : Pos, // centre
+	pub size : f64
+}
+
+/// All pellets currently lying around
+#[derive(Default, Serialize, Deserialize)]
+pub struct Food {
+	pellets: Vec<Pellet>,
+	patches: Vec<Pos>,
+
+	#[serde(skip)]
+	grid: Grid, // of pellet centres, rebuilt whenever pellets grow or are eaten
+	#[serde(skip)]
+	indexed: bool
+}
+
+/// The nearest pellet as sensed by one agent, normalised to [-1, 1]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Scent {
+	pub dist    : f64, // closeness, 1 when touching & 0 at the edge of the range or beyond
+	pub bearing : f64  // angle relative to heading
+}
+
+////////////////////////////////
+
+impl Food {
+	pub fn pellets(&self) -> &[Pellet] {&self.pellets}
+
+	/// Grows new pellets according to `FoodConfig::regrowth`
+	pub fn grow(&mut self, cfg: &SimConfig, rng: &mut SimRng) {
+		let food = &cfg.food;
+		if !food.enabled {return}
+
+		if food.distribution == Distribution::Patches && self.patches.len() != food.patches {
+			self.patches = (0..food.patches).map(|_| Pos::new(cfg, rng)).collect()
+		}
+
+		let mut n = food.regrowth.floor() as usize;
+		if rand_range(rng, 0.0..1.0) < food.regrowth.fract() {
+			n += 1
+		}
+
+		let n = n.min(food.max_pellets.saturating_sub(self.pellets.len()));
+		for _ in 0..n {
+			let pos = self.spawn_pos(cfg, rng);
+			self.pellets.push(Pellet {pos, size: food.pellet_size})
+		}
+
+		// Also after loading, as the grid isn't saved
+		if n > 0 || !self.indexed {
+			self.reindex(cfg)
+		}
+	}
+
+	/// Lets agents eat every pellet they touch, the largest one first if several do.
+	/// Returns how many pellets were eaten.
+	pub fn feed(&mut self, agents: &mut [Agent], grid: &mut Grid, cfg: &SimConfig) -> usize {
+		if !cfg.food.enabled || self.pellets.is_empty() {return 0}
+
+		grid.rebuild(agents, cfg);
+
+		let mut nearby = vec![];
+		let eaten = self.pellets.extract_if(.., |pellet| {
+			grid.overlapping(pellet.pos, 0.0, &mut nearby);
+
+			let eater = nearby.iter().copied()
+				.filter(|&i| agents[i].alive && touches(&agents[i].body, pellet.pos, cfg))
+				.max_by(|&i, &j| agents[i].body.size.total_cmp(&agents[j].body.size).then(j.cmp(&i)));
+
+			let Some(i) = eater else {return false};
+
+			let agent = &mut agents[i];
+			let size  = agent.body.size;
+
+			game::eat(&mut agent.body, size, pellet.size, 0.0, cfg);
+			agent.record_meal();
+			true
+		}).count();
+
+		if eaten > 0 {
+			self.reindex(cfg)
+		}
+		eaten
+	}
+
+	/// Senses the nearest pellet within range, if any
+	pub fn scent(&self, body: &Body, cfg: &SimConfig) -> Scent {
+		let range  = cfg.food.range;
+		let centre = body.pos;
+
+		let mut nearby = vec![];
+		self.grid.overlapping(centre, range, &mut nearby);
+
+		let nearest = nearby.into_iter()
+			.map(|i| (self.pellets[i].pos, centre.dist(self.pellets[i].pos, cfg)))
+			.filter(|&(_, dist)| dist <= range)
+			.min_by(|a, b| a.1.total_cmp(&b.1));
+
+		match nearest {
+			Some((pos, dist)) => {
+				let (dx, dy) = centre.delta(pos, cfg);
+				let bearing  = libm::atan2(dy, dx) - body.angle;
+
+				Scent {
+					dist    : 1.0 - dist / range,
+					bearing : libm::atan2(libm::sin(bearing), libm::cos(bearing)) / PI
+				}
+			},
+
+			None => Scent::default()
+		}
+	}
+
+	pub(crate) fn reindex(&mut self, cfg: &SimConfig) {
+		self.grid.rebuild_from(self.pellets.iter().map(|pellet| (pellet.pos, 0.0)), cfg);
+		self.indexed = true
+	}
+
+	fn spawn_pos(&self, cfg: &SimConfig, rng: &mut SimRng) -> Pos {
+		let (width, height) = (cfg.width, cfg.height);
+
+		match cfg.food.distribution {
+			Distribution::Uniform => Pos::new(cfg, rng),
+
+			Distribution::Patches => {
+				let Some(&patch) = self.patches.get(rand_range(rng, 0..self.patches.len().max(1))) else {
+					return Pos::new(cfg, rng)
+				};
+
+				// Uniformly within a disc around the patch
+				let radius = cfg.food.patch_radius * rand_range(rng, 0.0..1.0f64).sqrt();
+				let angle  = rand_range(rng, -PI..PI);
+
+				Pos {
+					x: (patch.x + radius*libm::cos(angle)).clamp(0.0, width),
+					y: (patch.y + radius*libm::sin(angle)).clamp(0.0, height)
+				}
+			},
+
+			// Mean of two uniform samples, i.e. a triangular distribution peaking in the middle
+			Distribution::Centre => Pos {
+				x: (rand_range(rng, 0.0..width)  + rand_range(rng, 0.0..width))  / 2.0,
+				y: (rand_range(rng, 0.0..height) + rand_range(rng, 0.0..height)) / 2.0
+			}
+		}
+	}
+}
+
+fn touches(body: &Body, pos: Pos, cfg: &SimConfig) -> bool {
+	body.pos.dist(pos, cfg) <= body.radius()
+}
+
+#[cfg(test)]
+mod tests {
+	use rand::SeedableRng;
+
+	use super::*;
+	use crate::spatial::tests::{population, random_world};
+
+	// Distance to the nearest pellet within range by checking every one
+	fn brute_force(food: &Food, body: &Body, cfg: &SimConfig) -> Option<f64> {
+		food.pellets.iter()
+			.map(|pellet| body.pos.dist(pellet.pos, cfg))
+			.filter(|&dist| dist <= cfg.food.range)
+			.min_by(f64::total_cmp)
+	}
+
+	#[test]
+	fn scent_matches_brute_force() {
+		let mut rng    = SimRng::seed_from_u64(10);
+		let mut agents = population(&mut rng);
+
+		for _ in 0..400 {
+			let (mut cfg, agents) = random_world(&mut agents, &mut rng);
+			cfg.food.range = rand_range(&mut rng, 1.0..600.0);
+
+			let mut food = Food::default();
+			for _ in 0..rand_range(&mut rng, 0..300) {
+				food.pellets.push(Pellet {pos: Pos::new(&cfg, &mut rng), size: cfg.food.pellet_size})
+			}
+			food.reindex(&cfg);
+
+			for agent in agents {
+				let scent = food.scent(&agent.body, &cfg);
+
+				match brute_force(&food, &agent.body, &cfg) {
+					Some(dist) => assert_eq!(scent.dist, 1.0 - dist / cfg.food.range, "in {cfg:?}"),
+					None       => assert_eq!((scent.dist, scent.bearing), (0.0, 0.0), "in {cfg:?}")
+				}
+			}
+		}
+	}
+}
@@ -1,100 +1,127 @@
 use std::f64::consts::PI;
 
-use crate::agent::*;
+use crate::{agent::*, config::SimConfig, metabolism, spatial::Grid};
 
-pub const GAME_SIZE: f64 = 600.0;
+/// Moves all agents & resolves collisions, returning the agents that died this tick
+pub fn update_game(agents: &mut Vec<Agent>, grid: &mut Grid, cfg: &SimConfig) -> Vec<(Agent, Death)> {
+	for agent in &mut *agents {
+		mov(&mut agent.body, cfg);
 
-const MOV_SPEED: f64 = 2.0;
-const ROT_SPEED: f64 = 0.1;
+		match cfg.metabolism.enabled {
+			true => metabolism::live(agent, &cfg.metabolism),
+			_    => shrink(&mut agent.body, cfg)
+		}
 
-pub fn update_game(agents: &mut Vec<Agent>) {
-	for agent in &mut *agents {
-		mov(&mut agent.body);
-		shrink(&mut agent.body);
+		agent.achievements.age += 1;
+		agent.since_meal       += 1
 	}
 
-	handle_collisions(agents);
+	handle_collisions(agents, grid, cfg)
 }
 
-fn mov(body: &mut Body) {
-	body.angle += ROT_SPEED * PI * body.rot.max(-1.0).min(1.0);
-	body.angle  = body.angle.sin().atan2(body.angle.cos()); // keep within [-PI, PI]
+fn mov(body: &mut Body, cfg: &SimConfig) {
+	body.angle += cfg.rot_speed * PI * body.rot.clamp(-1.0, 1.0);
+	body.angle  = libm::atan2(libm::sin(body.angle), libm::cos(body.angle)); // keep within [-PI, PI]
 
-	body.pos.x += MOV_SPEED * body.mov.max(-1.0).min(1.0) * body.angle.cos();
-	body.pos.y += MOV_SPEED * body.mov.max(-1.0).min(1.0) * body.angle.sin();
-}
+	body.pos.x += cfg.mov_speed * body.mov.clamp(-1.0, 1.0) * libm::cos(body.angle);
+	body.pos.y += cfg.mov_speed * body.mov.clamp(-1.0, 1.0) * libm::sin(body.angle);
 
-fn shrink(body: &mut Body) {
-	let size0 = body.size;
+	if cfg.wrap {
+		body.pos.x = body.pos.x.rem_euclid(cfg.width);
+		body.pos.y = body.pos.y.rem_euclid(cfg.height);
+	}
+}
 
+fn shrink(body: &mut Body, cfg: &SimConfig) {
 	let mov = body.mov.abs().min(1.0);
 	let rot = body.rot.abs().min(1.0);
 
 	// Movement & rotation costs energy (but always shrink a little regardless)
-	body.size  *= 0.9999_f64.powf(1.0 + mov/2.0 + rot/8.0);
-	body.pos.x += (size0 - body.size)/2.0;
-	body.pos.y += (size0 - body.size)/2.0;
+	body.size *= libm::pow(cfg.shrink_factor, 1.0 + mov/2.0 + rot/8.0);
 }
 
-fn handle_collisions(agents: &mut Vec<Agent>) {
+fn handle_collisions(agents: &mut Vec<Agent>, grid: &mut Grid, cfg: &SimConfig) -> Vec<(Agent, Death)> {
+	grid.rebuild(agents, cfg);
+
+	let mut nearby = vec![];
 	for i in 0..agents.len() {
 		if !agents[i].alive {continue} // skip dead agents
 
 		let (pos, size) = (agents[i].body.pos, agents[i].body.size);
 
-		// Check for collisions with other agents
-		for j in 0..agents.len() {
+		// Check for collisions with other nearby agents
+		grid.overlapping(pos, size/2.0, &mut nearby);
+		for &j in &nearby {
 			if i == j || !agents[j].alive {continue} // skip self & dead agents
 
 			let (pos2, size2) = (agents[j].body.pos, agents[j].body.size);
 
-			if closely_overlapping(pos, pos2, size, size2) {
-				if size > size2*1.1 {
+			if closely_overlapping(pos, pos2, size, size2, cfg) {
+				if size > size2*cfg.eat_ratio {
 					// #i larger => eats #j
-					eat(&mut agents[i].body, size, size2);
+					let energy2 = agents[j].body.energy;
+					eat(&mut agents[i].body, size, size2, energy2, cfg);
+					agents[i].record_kill();
 					agents[j].alive = false;
-				} else if size2 > size*1.1 {
+				} else if size2 > size*cfg.eat_ratio {
 					// #j larger => eats #i
-					eat(&mut agents[j].body, size2, size);
+					let energy = agents[i].body.energy;
+					eat(&mut agents[j].body, size2, size, energy, cfg);
+					agents[j].record_kill();
 					agents[i].alive = false;
 				}
 			}
 		}
 
-		// Ensure no agent goes outside the game borders
-		agents[i].body.pos.x = pos.x.min(GAME_SIZE - size as f64).max(0.0);
-		agents[i].body.pos.y = pos.y.min(GAME_SIZE - size as f64).max(0.0);
+		// Ensure no agent goes outside the game borders, unless it wraps around
+		if !cfg.wrap {
+			agents[i].body.pos.x = pos.x.min(cfg.width  - size/2.0).max(size/2.0);
+			agents[i].body.pos.y = pos.y.min(cfg.height - size/2.0).max(size/2.0);
+		}
 	}
 
 	// Remove dead agents
-	agents.retain(|agent| agent.alive && agent.body.size > 4.0);
+	let dead = agents.extract_if(.., |agent| !agent.alive || agent.body.size <= cfg.death_size)
+		.map(|agent| {
+			let cause = if agent.alive {Death::Starved} else {Death::Eaten};
+			(agent, cause)
+		})
+		.collect();
 
 	// Sort agents by size so that larger ones are drawn on top of smaller ones
-	agents.sort_unstable_by(|a, b| a.body.size.partial_cmp(&b.body.size).unwrap())
+	agents.sort_unstable_by(|a, b| a.body.size.partial_cmp(&b.body.size).unwrap());
+
+	dead
 }
 
-fn closely_overlapping(pos: Pos, pos2: Pos, size: f64, size2: f64) -> bool {
-	// Calculates the overlapping area and returns true if >90% overlapping area
+pub(crate) fn closely_overlapping(pos: Pos, pos2: Pos, size: f64, size2: f64, cfg: &SimConfig) -> bool {
+	// True if >90% of the smaller circle lies within the larger one
+	let r = size.min(size2)/2.0;
 
-	let overlap_x1 = pos.x.max(pos2.x);
-	let overlap_x2 = (pos.x+size).min(pos2.x+size2);
+	overlap_area(pos.dist(pos2, cfg), size/2.0, size2/2.0) > 0.9*PI*r*r
+}
 
-	let overlap_y1 = pos.y.max(pos2.y);
-	let overlap_y2 = (pos.y+size).min(pos2.y+size2);
+// Area of the lens where two circles intersect
+fn overlap_area(dist: f64, r1: f64, r2: f64) -> f64 {
+	if dist >= r1 + r2 {
+		return 0.0 // apart
+	}
+	if dist <= (r1 - r2).abs() {
+		let r = r1.min(r2);
+		return PI*r*r // one within the other
+	}
 
-	let overlap_width  = overlap_x2 - overlap_x1;
-	let overlap_height = overlap_y2 - overlap_y1;
+	let half_angle = |r: f64, r_other: f64| libm::acos(((dist*dist + r*r - r_other*r_other) / (2.0*dist*r)).clamp(-1.0, 1.0));
+	let kite = ((-dist + r1 + r2) * (dist + r1 - r2) * (dist - r1 + r2) * (dist + r1 + r2)).sqrt();
 
-	overlap_width                > 0.0                           &&
-	overlap_height               > 0.0                           &&
-	overlap_width*overlap_height > 0.9*size.min(size2).powf(2.0)
+	r1*r1*half_angle(r1, r2) + r2*r2*half_angle(r2, r1) - kite/2.0
 }
 
-fn eat(eater: &mut Body, size_l: f64, size_s: f64) {
-	let new_size_l = (size_l*size_l + size_s*size_s).sqrt();
-
-	eater.pos.x -= (new_size_l - size_l)/2.0;
-	eater.pos.y -= (new_size_l - size_l)/2.0;
+pub(crate) fn eat(eater: &mut Body, size_l: f64, size_s: f64, energy_s: f64, cfg: &SimConfig) {
+	// With a metabolism, eating fills the energy store & growth comes later
+	if cfg.metabolism.enabled {
+		return metabolism::digest(eater, size_s, energy_s, &cfg.metabolism)
+	}
 
-	eater.size = new_size_l;
+	eater.size = (size_l*size_l + size_s*size_s).sqrt()
 }
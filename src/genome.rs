@@ -0,0 +1,410 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::*, input::Input, neuron_model::ModelKind, output::Output};
+
+/// Bump whenever the genome layout changes, and keep accepting older versions
+pub const GENOME_VERSION: u16 = 5;
+
+const MAGIC: &[u8; 4] = b"NNEG";
+
+/// Stable interchange format for a single brain, independent of its runtime state
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+	pub version    : u16,
+	pub generation : usize,
+
+	pub inputs  : Vec<NeuronGene>,
+	pub hidden  : Vec<NeuronGene>,
+	pub outputs : Vec<NeuronGene>,
+
+	// Since version 4; before, inputs & outputs were always the first of
+	// `Input::LEGACY` & `Output::ALL`
+	#[serde(default)]
+	pub input_kinds: Vec<Input>,
+	#[serde(default)]
+	pub output_kinds: Vec<Output>
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NeuronGene {
+	pub tick_drain    : f64,
+	pub act_threshold : f64,
+	pub inv_mut       : usize,
+
+	#[serde(default)]
+	pub learn_rate: f64, // since version 2
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub model: Option<ModelKind>, // since version 3
+
+	#[serde(default)]
+	pub innovation: u64, // since version 5
+
+	pub conns: Vec<ConnGene>
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConnGene {
+	pub dest_index : usize,
+	pub weight     : f64,
+	pub relu       : bool,
+	pub speed      : usize,
+
+	#[serde(default)]
+	pub innovation: u64 // since version 5
+}
+
+////////////////////////////////
+
+impl Genome {
+	pub fn of(brain: &Brain) -> Genome {
+		let genes = |neurons: &[Neuron]| neurons.iter().map(NeuronGene::of).collect();
+
+		Genome {
+			version    : GENOME_VERSION,
+			generation : brain.generation,
+
+			inputs  : genes(&brain.neurons_inp),
+			hidden  : genes(&brain.neurons_hid),
+			outputs : genes(&brain.neurons_out),
+
+			input_kinds  : brain.input_kinds.clone(),
+			output_kinds : brain.output_kinds.clone()
+		}
+	}
+
+	/// Builds a fresh brain from this genome, with all runtime state reset.
+	/// Its inputs & outputs still have to be fitted to the config in use.
+	pub fn to_brain(&self) -> Result<Brain, String> {
+		self.validate()?;
+
+		let neurons = |genes: &[NeuronGene]| genes.iter().map(NeuronGene::to_neuron).collect::<Vec<_>>();
+
+		Ok(Brain {
+			neurons_inp: neurons(&self.inputs),
+			neurons_hid: neurons(&self.hidden),
+			neurons_out: neurons(&self.outputs),
+
+			input_kinds  : self.input_kinds(),
+			output_kinds : self.output_kinds(),
+
+			generation: self.generation,
+			in_flight: vec![],
+			clock: 0
+		})
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).expect("genome is always serialisable")
+	}
+
+	pub fn from_json(s: &str) -> Result<Genome, String> {
+		serde_json::from_str(s).map_err(|e| format!("invalid genome: {e}"))
+	}
+
+	/// Compact little-endian binary form: magic, version, generation,
+	/// then the input, hidden & output neuron sections, then the input & output kinds
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = MAGIC.to_vec();
+		bytes.extend(GENOME_VERSION.to_le_bytes());
+		bytes.extend((self.generation as u32).to_le_bytes());
+
+		for section in [&self.inputs, &self.hidden, &self.outputs] {
+			bytes.extend((section.len() as u32).to_le_bytes());
+
+			for neuron in section {
+				bytes.extend(neuron.tick_drain.to_le_bytes());
+				bytes.extend(neuron.act_threshold.to_le_bytes());
+				bytes.extend((neuron.inv_mut as u32).to_le_bytes());
+				bytes.extend(neuron.learn_rate.to_le_bytes());
+				bytes.push(model_code(neuron.model));
+				bytes.extend(neuron.innovation.to_le_bytes());
+				bytes.extend((neuron.conns.len() as u32).to_le_bytes());
+
+				for conn in &neuron.conns {
+					bytes.extend((conn.dest_index as u32).to_le_bytes());
+					bytes.extend(conn.weight.to_le_bytes());
+					bytes.push(conn.relu as u8);
+					bytes.extend((conn.speed as u32).to_le_bytes());
+					bytes.extend(conn.innovation.to_le_bytes());
+				}
+			}
+		}
+
+		// Kinds by name, so that adding new ones never breaks older genomes
+		let input_kinds  = self.input_kinds.iter().map(kind_name).collect::<Vec<_>>();
+		let output_kinds = self.output_kinds.iter().map(kind_name).collect::<Vec<_>>();
+
+		for kinds in [input_kinds, output_kinds] {
+			bytes.extend((kinds.len() as u32).to_le_bytes());
+			for kind in kinds {
+				bytes.push(kind.len() as u8);
+				bytes.extend(kind.as_bytes())
+			}
+		}
+
+		bytes
+	}
+
+	pub fn from_bytes(bytes: &[u8]) -> Result<Genome, String> {
+		let mut r = Reader {bytes};
+
+		if r.take(4)? != MAGIC {
+			return Err("invalid genome: not a genome file".into())
+		}
+
+		let version = u16::from_le_bytes(r.array()?);
+		if version > GENOME_VERSION {
+			return Err(format!("genome version {version} is newer than supported version {GENOME_VERSION}"))
+		}
+
+		let generation = r.u32()? as usize;
+
+		let mut sections = [vec![], vec![], vec![]];
+		for section in &mut sections {
+			for _ in 0..r.u32()? {
+				let tick_drain    = r.f64()?;
+				let act_threshold = r.f64()?;
+				let inv_mut       = r.u32()? as usize;
+				let learn_rate    = if version >= 2 {r.f64()?} else {0.0};
+				let model         = if version >= 3 {model_of_code(r.take(1)?[0])?} else {None};
+				let innovation    = if version >= 5 {r.u64()?} else {0};
+
+				let mut conns = vec![];
+				for _ in 0..r.u32()? {
+					conns.push(ConnGene {
+						dest_index : r.u32()? as usize,
+						weight     : r.f64()?,
+						relu       : r.take(1)?[0] != 0,
+						speed      : r.u32()? as usize,
+						innovation : if version >= 5 {r.u64()?} else {0}
+					})
+				}
+
+				section.push(NeuronGene {tick_drain, act_threshold, inv_mut, learn_rate, model, innovation, conns})
+			}
+		}
+
+		let (mut input_kinds, mut output_kinds) = (vec![], vec![]);
+		if version >= 4 {
+			input_kinds  = r.kinds()?;
+			output_kinds = r.kinds()?
+		}
+
+		if !r.bytes.is_empty() {
+			return Err("invalid genome: trailing bytes".into())
+		}
+
+		let [inputs, hidden, outputs] = sections;
+		Ok(Genome {version, generation, inputs, hidden, outputs, input_kinds, output_kinds})
+	}
+
+	fn validate(&self) -> Result<(), String> {
+		if self.version > GENOME_VERSION {
+			return Err(format!(
+				"genome version {} is newer than supported version {GENOME_VERSION}", self.version
+			))
+		}
+
+		if self.inputs.len() != self.input_kinds().len() || self.outputs.len() != self.output_kinds().len() {
+			return Err(format!(
+				"invalid genome: {} inputs & {} outputs, but {} input & {} output kinds",
+				self.inputs.len(), self.outputs.len(), self.input_kinds().len(), self.output_kinds().len()
+			))
+		}
+
+		if !unique(&self.input_kinds) || !unique(&self.output_kinds) {
+			return Err("invalid genome: duplicate input or output kinds".into())
+		}
+
+		let recv_neurons = self.outputs.len() + self.hidden.len();
+		for neuron in self.inputs.iter().chain(&self.hidden).chain(&self.outputs) {
+			if let Some(conn) = neuron.conns.iter().find(|conn| conn.dest_index >= recv_neurons) {
+				return Err(format!(
+					"invalid genome: connection to #{} but only {recv_neurons} receiving neurons",
+					conn.dest_index
+				))
+			}
+		}
+
+		Ok(())
+	}
+
+	fn input_kinds(&self) -> Vec<Input> {
+		match self.input_kinds.is_empty() {
+			true => Input::LEGACY.iter().copied().take(self.inputs.len()).collect(),
+			_    => self.input_kinds.clone()
+		}
+	}
+
+	fn output_kinds(&self) -> Vec<Output> {
+		match self.output_kinds.is_empty() {
+			true => Output::ALL.iter().copied().take(self.outputs.len()).collect(),
+			_    => self.output_kinds.clone()
+		}
+	}
+}
+
+impl NeuronGene {
+	fn of(neuron: &Neuron) -> NeuronGene {
+		NeuronGene {
+			tick_drain    : neuron.tick_drain,
+			act_threshold : neuron.act_threshold,
+			inv_mut       : neuron.inv_mut,
+
+			learn_rate : neuron.learn_rate,
+			model      : neuron.model,
+			innovation : neuron.innovation,
+
+			conns: neuron.next_conn.iter().map(|conn| ConnGene {
+				dest_index : conn.dest_index,
+				weight     : conn.weight,
+				relu       : conn.relu,
+				speed      : conn.speed,
+				innovation : conn.innovation
+			}).collect()
+		}
+	}
+
+	fn to_neuron(&self) -> Neuron {
+		Neuron {
+			excitation: 0.0,
+			tick_drain: self.tick_drain,
+
+			act_threshold: self.act_threshold,
+
+			next_conn: self.conns.iter().map(|conn| OutwardConn {
+				dest_index : conn.dest_index,
+				speed      : conn.speed,
+				weight     : conn.weight,
+				relu       : conn.relu,
+				innovation : conn.innovation
+			}).collect(),
+
+			innovation: self.innovation,
+
+			learn_rate: self.learn_rate,
+
+			model: self.model,
+			state: [0.0; 2],
+
+			reachable: false,
+
+			last_fired: None,
+
+			inv_mut: self.inv_mut
+		}
+	}
+}
+
+// 0 for none, otherwise 1 + index into `ModelKind::ALL`
+fn model_code(model: Option<ModelKind>) -> u8 {
+	model.map_or(0, |model| 1 + ModelKind::ALL.iter().position(|&m| m == model).expect("all models listed") as u8)
+}
+
+fn model_of_code(code: u8) -> Result<Option<ModelKind>, String> {
+	match code {
+		0 => Ok(None),
+		_ => ModelKind::ALL.get(code as usize - 1).copied().map(Some)
+			.ok_or_else(|| format!("invalid genome: unknown neuron model {code}"))
+	}
+}
+
+fn kind_name(kind: &impl Serialize) -> String {
+	serde_json::to_string(kind).expect("kinds are always serialisable")
+}
+
+fn unique<K: PartialEq>(kinds: &[K]) -> bool {
+	kinds.iter().enumerate().all(|(i, kind)| !kinds[..i].contains(kind))
+}
+
+////////////////////////////////
+
+struct Reader<'a> {bytes: &'a [u8]}
+
+impl<'a> Reader<'a> {
+	fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+		if self.bytes.len() < n {
+			return Err("invalid genome: unexpected end of data".into())
+		}
+
+		let (taken, rest) = self.bytes.split_at(n);
+		self.bytes = rest;
+		Ok(taken)
+	}
+
+	fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+		Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+	}
+
+	fn u32(&mut self) -> Result<u32, String> {Ok(u32::from_le_bytes(self.array()?))}
+
+	fn u64(&mut self) -> Result<u64, String> {Ok(u64::from_le_bytes(self.array()?))}
+
+	fn kinds<K: serde::de::DeserializeOwned>(&mut self) -> Result<Vec<K>, String> {
+		let mut kinds = vec![];
+		for _ in 0..self.u32()? {
+			let len = self.take(1)?[0] as usize;
+
+			let kind = std::str::from_utf8(self.take(len)?).ok().and_then(|s| serde_json::from_str(s).ok());
+			kinds.push(kind.ok_or("invalid genome: unknown input or output kind")?)
+		}
+
+		Ok(kinds)
+	}
+	fn f64(&mut self) -> Result<f64, String> {Ok(f64::from_le_bytes(self.array()?))}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{config::SimConfig, world::World};
+
+	// Genomes of a few evolved brains, of every neuron model
+	fn genomes() -> Vec<Genome> {
+		let config = SimConfig {evolve_neuron_model: true, ..SimConfig::default()};
+
+		let mut world = World::with_config(config, Some(3));
+		for _ in 0..2000 {
+			world.step()
+		}
+
+		world.agents().iter().map(|agent| Genome::of(&agent.brain)).collect()
+	}
+
+	#[test]
+	fn binary_and_json_round_trip() {
+		let genomes = genomes();
+		assert!(!genomes.is_empty());
+
+		for genome in genomes {
+			assert_eq!(Genome::from_bytes(&genome.to_bytes()).unwrap(), genome);
+			assert_eq!(Genome::from_json(&genome.to_json()).unwrap(), genome);
+
+			let brain = genome.to_brain().unwrap();
+			assert_eq!(Genome::of(&brain), genome)
+		}
+	}
+
+	#[test]
+	fn rejects_truncated_or_trailing_bytes() {
+		let bytes = genomes()[0].to_bytes();
+
+		for len in 0..bytes.len() {
+			assert!(Genome::from_bytes(&bytes[..len]).is_err(), "accepted {len} of {} bytes", bytes.len())
+		}
+
+		let mut trailing = bytes.clone();
+		trailing.push(0);
+		assert_eq!(Genome::from_bytes(&trailing), Err("invalid genome: trailing bytes".into()))
+	}
+
+	#[test]
+	fn rejects_other_files_and_newer_versions() {
+		assert!(Genome::from_bytes(b"PNG\x0d\x0a").is_err());
+
+		let mut bytes = genomes()[0].to_bytes();
+		bytes[4..6].copy_from_slice(&(GENOME_VERSION + 1).to_le_bytes());
+		assert!(Genome::from_bytes(&bytes).unwrap_err().contains("newer"))
+	}
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{agent::*, config::SimConfig, crossover::Innovations, genome::Genome, helpers::*, lineage::AgentIds};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Criterion {
+	#[default]
+	MaxSize,
+	Lifetime,
+	Kills,
+	Offspring
+}
+
+/// Keeps the best agents that have ever died, so that their brains outlive population crashes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HallOfFameConfig {
+	pub capacity  : usize, // 0 to disable
+	pub criterion : Criterion,
+
+	pub spawn_ratio: f64 // fraction of random spawns descending from the hall of fame instead
+}
+
+impl Default for HallOfFameConfig {
+	fn default() -> Self {
+		HallOfFameConfig {
+			capacity  : 16,
+			criterion : Criterion::MaxSize,
+
+			spawn_ratio: 0.0
+		}
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Entry {
+	pub id    : u64,
+	pub score : f64,
+
+	pub brain  : Brain,
+	pub colour : Colour,
+
+	inv_split_freq: usize
+}
+
+/// Best agents by `HallOfFameConfig::criterion`, best first
+#[derive(Default, Serialize, Deserialize)]
+pub struct HallOfFame {entries: Vec<Entry>}
+
+impl Criterion {
+	pub fn score(self, agent: &Agent) -> f64 {
+		let feats = &agent.achievements;
+
+		match self {
+			Criterion::MaxSize   => feats.max_size,
+			Criterion::Lifetime  => feats.age as f64,
+			Criterion::Kills     => feats.kills as f64,
+			Criterion::Offspring => feats.offspring as f64
+		}
+	}
+}
+
+impl HallOfFame {
+	pub fn entries(&self) -> &[Entry] {&self.entries}
+
+	/// Inducts a dead agent if it scores among the best so far
+	pub fn consider(&mut self, agent: &Agent, cfg: &HallOfFameConfig) {
+		let score = cfg.criterion.score(agent);
+
+		// Earlier entries win ties
+		let rank = self.entries.partition_point(|entry| entry.score >= score);
+		if rank >= cfg.capacity {return}
+
+		self.entries.insert(rank, Entry {
+			id: agent.id,
+			score,
+
+			brain  : agent.brain.clone(),
+			colour : agent.body.colour.clone(),
+
+			inv_split_freq: agent.inv_split_freq
+		});
+		self.entries.truncate(cfg.capacity)
+	}
+
+	/// In `HallOfFameConfig::spawn_ratio` of cases, spawns a mutated descendant of a random entry
+	pub fn maybe_spawn(&self, cfg: &SimConfig, ids: &mut AgentIds, innov: &mut Innovations, rng: &mut SimRng) -> Option<Agent> {
+		let ratio = cfg.hall_of_fame.spawn_ratio;
+		if self.entries.is_empty() || ratio <= 0.0 || rand_range(rng, 0.0..1.0) >= ratio {
+			return None
+		}
+
+		let entry = &self.entries[rand_range(rng, 0..self.entries.len())];
+
+		Some(Agent::descendant(&entry.brain, (entry.colour.clone(), entry.inv_split_freq), entry.id, cfg, ids, innov, rng))
+	}
+
+	pub fn to_json(&self) -> String {
+		let entries: Vec<_> = self.entries.iter().map(|entry| json!({
+			"id"     : entry.id,
+			"score"  : entry.score,
+			"genome" : Genome::of(&entry.brain)
+		})).collect();
+
+		serde_json::to_string_pretty(&entries).expect("hall of fame is always serialisable")
+	}
+}
@@ -1,5 +1,11 @@
-use std::ops::RangeBounds;
-use rand::{Rng, distributions::uniform::{SampleRange, SampleUniform}};
+use std::ops::{Bound, RangeBounds};
+use rand::{Rng, distributions::uniform::SampleUniform};
+use rand_chacha::ChaCha8Rng;
+
+/// The one RNG every random decision in a world is drawn from, so that the same seed
+/// always replays the same simulation, natively & on wasm alike. For the latter, `sin`,
+/// `atan2`, `exp` etc. go through the `libm` crate rather than the platform's maths.
+pub type SimRng = ChaCha8Rng;
 
 pub trait BoundedSignedAdd {
 	fn add_bounded     (&mut self, val: isize);
@@ -22,9 +28,99 @@ impl BoundedSignedAdd for usize {
 	}
 }
 
-pub fn rand_range<T, R>(range: R) -> T
-	where T: SampleUniform,
-	      R: RangeBounds<T> + SampleRange<T>
-{
-	rand::thread_rng().gen_range(range)
+/// Draws from a `start..end` or `start..=end` range
+pub fn rand_range<T: Portable>(rng: &mut SimRng, range: impl RangeBounds<T>) -> T {
+	let sampled = match (range.start_bound(), range.end_bound()) {
+		(Bound::Included(&start), Bound::Excluded(&end)) => rng.gen_range(start.widen()..end.widen()),
+		(Bound::Included(&start), Bound::Included(&end)) => rng.gen_range(start.widen()..=end.widen()),
+		_ => panic!("rand_range needs a range with a start & an end")
+	};
+
+	T::narrow(sampled)
+}
+
+/// Something `rand_range` can draw, consuming the same random words on every platform.
+/// `usize` & `isize` are sampled as 64-bit integers, as they are only 32 bits wide on wasm.
+pub trait Portable: Copy {
+	type Wide: SampleUniform + PartialOrd + Copy;
+
+	fn widen(self) -> Self::Wide;
+	fn narrow(wide: Self::Wide) -> Self;
+}
+
+impl Portable for usize {
+	type Wide = u64;
+
+	fn widen(self) -> u64 {self as u64}
+	fn narrow(wide: u64) -> usize {wide as usize}
+}
+
+impl Portable for isize {
+	type Wide = i64;
+
+	fn widen(self) -> i64 {self as i64}
+	fn narrow(wide: i64) -> isize {wide as isize}
+}
+
+macro_rules! portable_as_is {
+	($($t:ty),*) => {$(
+		impl Portable for $t {
+			type Wide = $t;
+
+			fn widen(self) -> $t {self}
+			fn narrow(wide: $t) -> $t {wide}
+		}
+	)*}
+}
+portable_as_is!(i32, f64);
+
+/// Serde helper for floats that may run off to infinity or NaN (e.g. runaway excitation),
+/// which JSON can't represent; such values are stored as strings instead
+pub mod non_finite {
+	use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(val: &f64, s: S) -> Result<S::Ok, S::Error> {
+		if val.is_finite() {
+			s.serialize_f64(*val)
+		} else {
+			s.serialize_str(&val.to_string())
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<f64, D::Error> {
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Repr {Num(f64), Str(String)}
+
+		match Repr::deserialize(d)? {
+			Repr::Num(val) => Ok(val),
+			Repr::Str(s)   => s.parse().map_err(D::Error::custom)
+		}
+	}
+}
+
+/// Like `non_finite`, for fixed-size arrays of floats
+pub mod non_finite_array {
+	use serde::{de::Error, ser::SerializeTuple, Deserialize, Deserializer, Serialize, Serializer};
+
+	#[derive(Serialize, Deserialize)]
+	#[serde(transparent)]
+	struct Float(#[serde(with = "super::non_finite")] f64);
+
+	pub fn serialize<S: Serializer, const N: usize>(vals: &[f64; N], s: S) -> Result<S::Ok, S::Error> {
+		let mut tuple = s.serialize_tuple(N)?;
+		for &val in vals {
+			tuple.serialize_element(&Float(val))?
+		}
+		tuple.end()
+	}
+
+	// Also accepts null, which is how NaN used to be stored before this helper was used
+	pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(d: D) -> Result<[f64; N], D::Error> {
+		let vals: Vec<Option<Float>> = Vec::deserialize(d)?;
+		let len = vals.len();
+
+		vals.into_iter().map(|val| val.map_or(f64::NAN, |Float(val)| val)).collect::<Vec<_>>().try_into()
+			.map_err(|_| D::Error::invalid_length(len, &format!("an array of {N} floats").as_str()))
+	}
 }
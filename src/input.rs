@@ -1,79 +1,257 @@
 use std::f64::consts::PI;
-use crate::{agent::*, game::GAME_SIZE};
 
-const MAX_DIST: f64 = 259_200_000_000.0;
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::*, config::SimConfig, food::Scent, perception::{Percept, Sense}, spatial::Grid};
+use crate::vision::{RayChannel, Vision};
+
+/// What each input neuron senses
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Input {
+	SizeDiff, // nearest agent smaller (1), larger (-1) or neither (0)
+	Dist,     // closeness of nearest agent
+	Angle,    // angle towards nearest agent
+	TouchingEdge,
+
+	Neighbour {rank: usize, sense: Sense}, // k-th nearest agent, see `PerceptionConfig::neighbours`
+	Sector {index: usize, sense: Sense},   // nearest agent in a sector, see `PerceptionConfig::sectors`
+
+	Ray {index: usize, channel: RayChannel}, // see `VisionConfig`
+
+	FoodDist,  // closeness of nearest pellet, see `FoodConfig`
+	FoodAngle, // angle towards nearest pellet
+
+	Own(Own) // see `SimConfig::proprioception`
+}
+
+/// An agent's own state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Own {
+	Size,
+	Energy,   // reserve left before starving
+	Speed,    // `Body::mov`
+	Rotation, // `Body::rot`
+	Heading,  // `Body::angle`
+	Age,
+	Hunger    // time since last meal
+}
+
+impl Input {
+	/// The original fixed inputs, assumed for brains & genomes predating configurable inputs
+	pub(crate) const LEGACY: [Input; 4] = [Input::SizeDiff, Input::Dist, Input::Angle, Input::TouchingEdge];
+}
+
+/// The inputs enabled by the config, in neuron order
+pub fn layout(cfg: &SimConfig) -> Vec<Input> {
+	let vision = &cfg.vision;
+
+	let mut inputs = match vision.rays > 0 && vision.replace_nearest {
+		true => vec![Input::SizeDiff, Input::Dist], // size & distance of nearest still useful
+		_    => Input::LEGACY.to_vec()
+	};
+
+	for rank in 0..cfg.perception.neighbours {
+		inputs.extend(Sense::ALL.map(|sense| Input::Neighbour {rank, sense}))
+	}
+	for index in 0..cfg.perception.sectors {
+		inputs.extend([Sense::RelSize, Sense::Dist].map(|sense| Input::Sector {index, sense}))
+	}
+	for index in 0..vision.rays {
+		inputs.extend(RayChannel::layout(vision).iter().map(|&channel| Input::Ray {index, channel}))
+	}
+	if cfg.food.enabled && cfg.food.sense {
+		inputs.extend([Input::FoodDist, Input::FoodAngle])
+	}
+	inputs.extend(cfg.proprioception.iter().map(|&own| Input::Own(own)));
+
+	inputs
+}
+
+/// Everything an agent perceives in a tick
+pub struct Senses {
+	pub nearest : Nearest,
+	pub percept : Percept,
+	pub vision  : Vision,
+	pub food    : Scent
+}
 
 ////////////////////////////////
 
-pub fn assign(input: &mut [Neuron; 4], body: &Body, nearest: Nearest) {
-	// Relative size of nearest as first input
-	input[0].excitation = if body.size > nearest.size*1.1 {
-		1.0
-	} else if nearest.size > body.size*1.1 {
-		-1.0
-	} else {0.0};
+pub fn assign(agent: &mut Agent, senses: &Senses, cfg: &SimConfig) {
+	let (brain, body) = (&mut agent.brain, &agent.body);
+	let nearest = &senses.nearest;
+
+	for (neuron, input) in brain.neurons_inp.iter_mut().zip(&brain.input_kinds) {
+		neuron.excitation = match input {
+			Input::SizeDiff => if body.size > nearest.size*cfg.eat_ratio {
+				1.0
+			} else if nearest.size > body.size*cfg.eat_ratio {
+				-1.0
+			} else {0.0},
+
+			Input::Dist  => 1.0 - nearest.dist.max(0.0) / max_dist(cfg),
+			Input::Angle => nearest.angle / PI,
+
+			Input::TouchingEdge => touching_edge(body, cfg).into(),
+
+			// Nothing in sight senses as 0
+			&Input::Neighbour {rank, sense} => senses.percept.neighbours.get(rank).map_or(0.0, |seen| seen.sense(sense)),
+			&Input::Sector {index, sense}   => senses.percept.sectors.get(index).copied().flatten().map_or(0.0, |seen| seen.sense(sense)),
+
+			&Input::Ray {index, channel} => senses.vision.sense(index, channel, cfg),
+
+			Input::FoodDist  => senses.food.dist,
+			Input::FoodAngle => senses.food.bearing,
+
+			&Input::Own(own) => match own {
+				Own::Size     => (body.size / cfg.split_size).min(2.0) - 1.0,
+				Own::Energy   => energy(body, cfg),
+				Own::Speed    => body.mov.clamp(-1.0, 1.0),
+				Own::Rotation => body.rot.clamp(-1.0, 1.0),
+				Own::Heading  => body.angle / PI,
+				Own::Age      => saturate(agent.achievements.age),
+				Own::Hunger   => saturate(agent.since_meal)
+			}
+		}
+	}
+}
 
-	// Distance to nearest as second input
-	input[1].excitation = nearest.inv_dist / MAX_DIST;
+// How full the energy store is, or without a metabolism how far from starving
+fn energy(body: &Body, cfg: &SimConfig) -> f64 {
+	match cfg.metabolism.enabled {
+		true => (body.energy / (cfg.metabolism.reserve * body.size*body.size)).clamp(0.0, 1.0),
+		_    => ((body.size - cfg.death_size) / (cfg.split_size - cfg.death_size)).clamp(0.0, 1.0)
+	}
+}
+
+// Maps a number of ticks to [0, 1), reaching 0.5 after 1000 ticks
+fn saturate(ticks: u64) -> f64 {
+	ticks as f64 / (ticks as f64 + 1000.0)
+}
 
-	// Angle towards nearest as third input
-	input[2].excitation = nearest.angle / PI;
+// Never in a world without edges
+fn touching_edge(body: &Body, cfg: &SimConfig) -> bool {
+	let r = body.radius();
 
-	// Touching edge or not as fourth input
-	input[3].excitation = touching_edge(body).into()
+	!cfg.wrap && (
+		body.pos.x <= r              ||
+		body.pos.y <= r              ||
+		body.pos.x >= cfg.width  - r ||
+		body.pos.y >= cfg.height - r
+	)
 }
 
-fn touching_edge(body: &Body) -> bool {
-	body.pos.x == 0.0                   ||
-	body.pos.y == 0.0                   ||
-	body.pos.x == GAME_SIZE - body.size ||
-	body.pos.y == GAME_SIZE - body.size
+// Farthest any two agents can be apart, corner to corner
+fn max_dist(cfg: &SimConfig) -> f64 {
+	libm::hypot(cfg.width, cfg.height)
 }
 
 ////////////////////////////////
 
 pub struct Nearest {
-	size     : f64,
-	inv_dist : f64,
-	angle    : f64
+	size  : f64,
+	dist  : f64, // between the edges, negative if overlapping
+	angle : f64
 }
 
 impl Nearest {
-	pub fn to(agents: &Vec<Agent>, i: usize) -> Self {
-		let mut nearest = Nearest {
-			size     : 0.0,
-			inv_dist : 0.0,
-			angle    : 0.0
-		};
-
-		// Find the nearest agent
-		for j in 0..agents.len() {
-			if i == j || agents[j].body.size < 32.0 {continue}
-
-			let inv_dist_to_j = Self::inv_dist(agents[i].body.pos, agents[j].body.pos);
-			if inv_dist_to_j > nearest.inv_dist {
-				nearest = Nearest {
-					size     : agents[j].body.size,
-					inv_dist : inv_dist_to_j,
-					angle    : Self::angle_between(&agents[i].body, &agents[j].body)
+	pub fn to(agents: &[Agent], i: usize, grid: &Grid, cfg: &SimConfig) -> Self {
+		let body = &agents[i].body;
+
+		// Find the nearest agent (ties going to the lowest index),
+		// searching outwards ring by ring until nothing further out could be nearer
+		let mut nearest: Option<(usize, f64)> = None;
+		for ring in 0..=grid.max_ring() {
+			for j in grid.ring(body.pos, ring) {
+				if i == j || agents[j].body.size < cfg.food_size {continue}
+
+				let dist_to_j = Self::dist(body, &agents[j].body, cfg);
+				let is_nearer = match nearest {
+					Some((k, dist)) => dist_to_j < dist || (dist_to_j == dist && j < k),
+					None            => true
+				};
+
+				if is_nearer {
+					nearest = Some((j, dist_to_j))
 				}
 			}
+
+			// Anything not found yet lies entirely beyond this ring
+			if let Some((_, dist)) = nearest {
+				if dist <= grid.min_dist_beyond(ring) - body.radius() {break}
+			}
 		}
 
-		nearest
+		match nearest {
+			Some((j, dist)) => Nearest {
+				size  : agents[j].body.size,
+				dist,
+				angle : Self::angle_between(body, &agents[j].body, cfg)
+			},
+
+			None => Nearest {
+				size  : 0.0,
+				dist  : max_dist(cfg),
+				angle : 0.0
+			}
+		}
 	}
 
 	fn norm_angle(angle: f64) -> f64 {
-		angle.sin().atan2(angle.cos()) // trick to get angle within [-PI, PI]
+		libm::atan2(libm::sin(angle), libm::cos(angle)) // trick to get angle within [-PI, PI]
 	}
 
 	// Note: returns radians within [-PI, PI]
-	fn angle_between(b1: &Body, b2: &Body) -> f64 {
-		Self::norm_angle(b1.angle - (b2.pos.y - b1.pos.y).atan2(b2.pos.x - b1.pos.x))
+	fn angle_between(b1: &Body, b2: &Body, cfg: &SimConfig) -> f64 {
+		let (dx, dy) = b1.pos.delta(b2.pos, cfg);
+		Self::norm_angle(b1.angle - libm::atan2(dy, dx))
+	}
+
+	fn dist(b1: &Body, b2: &Body, cfg: &SimConfig) -> f64 {
+		b1.pos.dist(b2.pos, cfg) - b1.radius() - b2.radius()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rand::SeedableRng;
+
+	use super::*;
+	use crate::{helpers::SimRng, spatial::tests::{population, random_world}};
+
+	// Nearest agent by checking every other one, ties going to the lowest index
+	fn brute_force(agents: &[Agent], i: usize, cfg: &SimConfig) -> Option<usize> {
+		(0..agents.len())
+			.filter(|&j| j != i && agents[j].body.size >= cfg.food_size)
+			.min_by(|&j, &k| {
+				let dist = |j: usize| Nearest::dist(&agents[i].body, &agents[j].body, cfg);
+				dist(j).total_cmp(&dist(k)).then(j.cmp(&k))
+			})
 	}
 
-	fn inv_dist(pos1: Pos, pos2: Pos) -> f64 {
-		(GAME_SIZE - (pos1.x - pos2.x).abs()).powf(4.0) +
-		(GAME_SIZE - (pos1.y - pos2.y).abs()).powf(4.0)
+	#[test]
+	fn nearest_matches_brute_force() {
+		let mut rng    = SimRng::seed_from_u64(9);
+		let mut agents = population(&mut rng);
+		let mut grid   = Grid::default();
+
+		for _ in 0..400 {
+			let (cfg, agents) = random_world(&mut agents, &mut rng);
+			grid.rebuild(agents, &cfg);
+
+			for i in 0..agents.len() {
+				let nearest = Nearest::to(agents, i, &grid, &cfg);
+
+				match brute_force(agents, i, &cfg) {
+					Some(j) => {
+						assert_eq!(nearest.dist, Nearest::dist(&agents[i].body, &agents[j].body, &cfg), "agent {i} in {cfg:?}");
+						assert_eq!(nearest.size, agents[j].body.size, "agent {i} in {cfg:?}")
+					}
+					None => assert_eq!(nearest.dist, max_dist(&cfg))
+				}
+			}
+		}
 	}
 }
@@ -1,20 +1,41 @@
 use wasm_bindgen::prelude::*;
 
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 extern "C" {
 	#[wasm_bindgen(js_namespace = console)]
 	pub fn log(s: &str);
+}
+
+// Native builds have no console to log to, so print instead
+#[cfg(not(target_arch = "wasm32"))]
+pub fn log(s: &str) {
+	println!("{s}")
+}
 
+#[wasm_bindgen]
+extern "C" {
 	#[wasm_bindgen(js_namespace = window)]
-	pub fn draw_bg();
+	pub fn draw_bg(canvas: &JsValue);
 
 	#[wasm_bindgen(js_namespace = window)]
-	pub fn draw_agent(r: usize, g: usize, b: usize, x: f64, y: f64, size: f64);
+	pub fn draw_agent(canvas: &JsValue, r: usize, g: usize, b: usize, x: f64, y: f64, size: f64, angle: f64);
 
+	#[wasm_bindgen(js_namespace = window)]
+	pub fn draw_pellet(canvas: &JsValue, x: f64, y: f64, size: f64);
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
 	#[wasm_bindgen(js_namespace = window)]
 	pub fn draw_neural_network(s: String);
 }
 
+// Native builds have nowhere to draw it, and it has been logged already
+#[cfg(not(target_arch = "wasm32"))]
+pub fn draw_neural_network(_s: String) {}
+
 macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
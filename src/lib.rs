@@ -0,0 +1,49 @@
+// $ cargo install wasm-bindgen
+// $ cargo install wasm-bindgen-cli
+// $ compile
+
+#[macro_use]
+mod js;
+mod helpers;
+mod game;
+
+mod ai;
+mod crossover;
+mod input;
+mod metabolism;
+mod output;
+mod plasticity;
+mod snapshot;
+mod spatial;
+
+pub mod agent;
+pub mod config;
+pub mod food;
+pub mod genome;
+pub mod hall_of_fame;
+pub mod lineage;
+pub mod neuron_model;
+pub mod perception;
+pub mod species;
+pub mod stats;
+pub mod vision;
+pub mod world;
+
+use wasm_bindgen::prelude::*;
+
+use js::*;
+
+pub use world::World;
+pub use config::SimConfig;
+pub use snapshot::SNAPSHOT_VERSION;
+pub use genome::{Genome, GENOME_VERSION};
+pub use helpers::SimRng;
+pub use input::Input;
+pub use output::Output;
+
+////////////////////////////////
+
+#[wasm_bindgen(start)]
+pub fn start() {
+	console_log!("Starting version 0.5.4")
+}
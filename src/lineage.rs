@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+use crate::agent::*;
+
+/// Hands out unique agent ids, in order of birth
+#[derive(Default, Serialize, Deserialize)]
+pub struct AgentIds {next: u64}
+
+impl AgentIds {
+	pub fn next_id(&mut self) -> u64 {
+		self.next += 1;
+		self.next - 1
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Record {
+	pub id     : u64,
+	pub parent : Option<u64>, // None for randomly spawned agents
+	#[serde(default)]
+	pub mate   : Option<u64>, // other parent, if born through mating
+
+	pub generation: usize,
+
+	pub born  : u64,
+	pub died  : Option<u64>,
+	pub cause : Option<Death>
+}
+
+/// Genealogy of every agent that has ever lived in a world
+#[derive(Default, Serialize, Deserialize)]
+pub struct Lineage {
+	pub ids: AgentIds,
+
+	records: Vec<Record> // ordered by id, since ids are handed out in order of birth
+}
+
+impl Lineage {
+	pub fn records(&self) -> &[Record] {&self.records}
+
+	pub fn get(&self, id: u64) -> Option<&Record> {
+		self.index_of(id).map(|i| &self.records[i])
+	}
+
+	pub fn record_birth(&mut self, agent: &Agent, tick: u64) {
+		self.records.push(Record {
+			id     : agent.id,
+			parent : agent.parent,
+			mate   : agent.mate,
+
+			generation: agent.brain.generation,
+
+			born  : tick,
+			died  : None,
+			cause : None
+		})
+	}
+
+	pub fn record_death(&mut self, agent: &Agent, tick: u64, cause: Death) {
+		if let Some(i) = self.index_of(agent.id) {
+			self.records[i].died  = Some(tick);
+			self.records[i].cause = Some(cause)
+		}
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(&self.records).expect("lineage is always serialisable")
+	}
+
+	/// One Newick tree per randomly spawned ancestor, with branch lengths in ticks.
+	/// Nodes are labelled with agent ids.
+	pub fn to_newick(&self) -> String {
+		let mut children = vec![vec![]; self.records.len()];
+		let mut roots    = vec![];
+
+		for (i, record) in self.records.iter().enumerate() {
+			match record.parent.and_then(|id| self.index_of(id)) {
+				Some(parent) => children[parent].push(i),
+				None         => roots.push(i)
+			}
+		}
+
+		enum Step {Open(usize, bool), Close(usize)}
+
+		let mut s = String::new();
+		for root in roots {
+			// Iterative depth-first traversal, since lineages can get very deep
+			let mut stack = vec![Step::Open(root, false)];
+			while let Some(step) = stack.pop() {
+				match step {
+					Step::Open(i, after_sibling) => {
+						if after_sibling {s += ","}
+
+						if children[i].is_empty() {
+							s += &self.newick_node(i)
+						} else {
+							s += "(";
+							stack.push(Step::Close(i));
+							stack.extend(children[i].iter().enumerate().rev().map(|(k, &child)| Step::Open(child, k > 0)))
+						}
+					}
+
+					Step::Close(i) => s += &format!("){}", self.newick_node(i))
+				}
+			}
+
+			s += ";\n"
+		}
+
+		s
+	}
+
+	// Label & branch length (ticks since the parent's birth) of a node
+	fn newick_node(&self, i: usize) -> String {
+		let record = &self.records[i];
+		let born0  = record.parent.and_then(|id| self.get(id)).map_or(0, |parent| parent.born);
+
+		format!("{}:{}", record.id, record.born - born0)
+	}
+
+	fn index_of(&self, id: u64) -> Option<usize> {
+		self.records.binary_search_by_key(&id, |record| record.id).ok()
+	}
+}
@@ -1,81 +1,207 @@
-// $ cargo install wasm-bindgen
-// $ cargo install wasm-bindgen-cli
-// $ compile
-
-#[macro_use]
-mod js;
-mod helpers;
-mod game;
+// Headless native runner, for long evolution runs without a browser
+//
+// $ cargo run --release -- --ticks 1000000 --spawn-rate 32 --checkpoint 10000 --out runs/overnight
+
+use std::{env, fs, path::{Path, PathBuf}, process};
+
+use neural_network_evolution::{Genome, SimConfig, World};
+
+const USAGE: &str = "\
+Usage: neural-network-evolution [OPTIONS]
+
+Options:
+  --ticks <N>        number of ticks to simulate     [default: 100000]
+  --config <FILE>    simulation config (.toml/.json) [default: built-in]
+  --spawn-rate <N>   inverse random spawn rate       [default: from config]
+  --seed <N>         RNG seed, for reproducible runs [default: random]
+  --load <FILE>      resume from a world snapshot
+  --genome <FILE>    spawn an agent from a genome (.json/binary), repeatable
+  --out <DIR>        directory to write results into [default: out]
+  --report <N>       print progress every N ticks    [default: 1000]
+  --checkpoint <N>   save snapshot & stats every N ticks [default: never]
+  -h, --help         print this help";
+
+struct Args {
+	ticks      : u64,
+	config     : Option<SimConfig>,
+	spawn_rate : Option<usize>,
+	seed       : Option<u64>,
+	load       : Option<PathBuf>,
+	genomes    : Vec<PathBuf>,
+	out_dir    : PathBuf,
+	report     : u64,
+	checkpoint : u64
+}
 
-mod ai;
-mod agent;
-mod input;
-mod output;
+fn main() {
+	let args = parse_args().unwrap_or_else(|err| {
+		eprintln!("error: {err}\n\n{USAGE}");
+		process::exit(2)
+	});
 
-use std::sync::Mutex;
+	let mut world = init_world(&args).unwrap_or_else(|err| {
+		eprintln!("error: {err}");
+		process::exit(1)
+	});
 
-use wasm_bindgen::prelude::*;
+	println!("Running with seed {} from tick {}", world.seed(), world.tick());
 
-use {agent::*, helpers::*, js::*};
-use game::update_game;
-use ai::update_ai;
+	for _ in 0..args.ticks {
+		world.step();
 
-// Need static mutex to work with WASM
-static AGENTS: Mutex<Vec<Agent>> = Mutex::new(vec![]);
+		if args.report > 0 && world.tick().is_multiple_of(args.report) {
+			println!("tick {}: {} agents alive", world.tick(), world.population())
+		}
 
-////////////////////////////////
+		// So that a run killed halfway can still be resumed with `--load`
+		if args.checkpoint > 0 && world.tick().is_multiple_of(args.checkpoint) {
+			if let Err(err) = write_checkpoint(&world, &args) {
+				eprintln!("error: failed to write checkpoint to {}: {err}", args.out_dir.display());
+				process::exit(1)
+			}
+		}
+	}
 
-#[wasm_bindgen(start)]
-pub fn start() {
-	console_log!("Starting version 0.5.4")
+	if let Err(err) = write_results(&world, &args) {
+		eprintln!("error: failed to write results to {}: {err}", args.out_dir.display());
+		process::exit(1)
+	}
 }
 
-#[wasm_bindgen]
-pub fn run(inverse_spawn_rate: usize) {
-	let mut agents = AGENTS.lock().unwrap();
+fn parse_args() -> Result<Args, String> {
+	let mut args = Args {
+		ticks      : 100_000,
+		config     : None,
+		spawn_rate : None,
+		seed       : None,
+		load       : None,
+		genomes    : vec![],
+		out_dir    : PathBuf::from("out"),
+		report     : 1000,
+		checkpoint : 0
+	};
+
+	let mut iter = env::args().skip(1);
+	while let Some(flag) = iter.next() {
+		if flag == "-h" || flag == "--help" {
+			println!("{USAGE}");
+			process::exit(0)
+		}
+
+		let val = iter.next().ok_or(format!("missing value for '{flag}'"))?;
+		let num = || val.parse().map_err(|_| format!("invalid value '{val}' for '{flag}'"));
+
+		match flag.as_str() {
+			"--ticks"      => args.ticks      = num()?,
+			"--config"     => args.config     = Some(load_config(&val)?),
+			"--spawn-rate" => args.spawn_rate = Some(num()? as usize),
+			"--seed"       => args.seed       = Some(num()?),
+			"--load"       => args.load       = Some(PathBuf::from(&val)),
+			"--genome"     => args.genomes.push(PathBuf::from(&val)),
+			"--out"        => args.out_dir    = PathBuf::from(&val),
+			"--report"     => args.report     = num()?,
+			"--checkpoint" => args.checkpoint = num()?,
+			_              => return Err(format!("unknown option '{flag}'"))
+		}
+	}
 
-	// Randomly spawn new agents
-	if rand_range(0..inverse_spawn_rate) == 0 {
-		agents.push(Agent::new())
+	if args.spawn_rate == Some(0) {
+		return Err("'--spawn-rate' must be at least 1".into())
 	}
 
-	if let Some(agent) = Agent::maybe_split(&mut agents) {
-		agents.push(agent)
+	if args.load.is_some() && args.seed.is_some() {
+		return Err("'--seed' can't be used with '--load'; the snapshot has its own RNG state".into())
 	}
 
-	update_ai(&mut agents);
-	update_game(&mut agents);
-	draw_frame(&agents);
+	Ok(args)
 }
 
-#[wasm_bindgen]
-pub fn print_agent_at(x: f64, y: f64) {
-	let agents = AGENTS.lock().unwrap();
-	for agent in agents.iter() {
-		let (pos, size) = (agent.body.pos, agent.body.size);
+fn load_config(path: &str) -> Result<SimConfig, String> {
+	let s = fs::read_to_string(path).map_err(|e| format!("failed to read '{path}': {e}"))?;
+
+	if path.ends_with(".json") {
+		SimConfig::from_json(&s)
+	} else {
+		SimConfig::from_toml(&s)
+	}
+}
 
-		if (pos.x..pos.x+size).contains(&x) && (pos.y..pos.y+size).contains(&y) {
-			let network = format!("Neural Network @ ({x}, {y}): {:#?}", agent.brain);
+fn init_world(args: &Args) -> Result<World, String> {
+	let mut world = match &args.load {
+		Some(path) => {
+			let s = fs::read_to_string(path)
+				.map_err(|e| format!("failed to read '{}': {e}", path.display()))?;
 
-			console_log!("{network}\n\nAGENTS ALIVE: {}", agents.len());
-			draw_neural_network(network);
+			World::load_snapshot(&s)?
 		}
+
+		None => World::with_config(SimConfig::default(), args.seed)
+	};
+
+	// Explicit options override whatever config the world already had
+	if let Some(config) = &args.config {
+		world.set_config(config.clone())
 	}
+
+	if let Some(rate) = args.spawn_rate {
+		world.set_inverse_spawn_rate(rate)
+	}
+
+	for path in &args.genomes {
+		let bytes = fs::read(path).map_err(|e| format!("failed to read '{}': {e}", path.display()))?;
+
+		let genome = if path.extension().is_some_and(|ext| ext == "json") {
+			Genome::from_json(&String::from_utf8_lossy(&bytes))?
+		} else {
+			Genome::from_bytes(&bytes)?
+		};
+
+		world.spawn_genome(&genome).map_err(|e| format!("'{}': {e}", path.display()))?
+	}
+
+	Ok(world)
 }
 
-////////////////////////////////
+fn write_checkpoint(world: &World, args: &Args) -> std::io::Result<()> {
+	fs::create_dir_all(&args.out_dir)?;
 
-fn main() {
-	println!("Don't run it this way; compile to wasm!")
+	write_atomic(&args.out_dir.join("stats.csv"),     world.stats().to_csv())?;
+	write_atomic(&args.out_dir.join("snapshot.json"), world.save_snapshot())
 }
 
-fn draw_frame(agents: &Vec<Agent>) {
-	console_log!("Redrawing frame.");
-	draw_bg();
-	for agent in agents {
-		let Colour {r, g, b} = agent.body.colour;
-		let Pos    {x, y}    = agent.body.pos;
+// Writes to a temporary file first, so that being killed mid-write never leaves a truncated file behind
+fn write_atomic(path: &Path, contents: String) -> std::io::Result<()> {
+	let tmp = path.with_extension("tmp");
 
-		draw_agent(r, g, b, x, y, agent.body.size)
+	fs::write(&tmp, contents)?;
+	fs::rename(tmp, path)
+}
+
+fn write_results(world: &World, args: &Args) -> std::io::Result<()> {
+	fs::create_dir_all(&args.out_dir)?;
+
+	let mut s = format!("SEED: {}\nAGENTS ALIVE AFTER {} TICKS: {}\n\n",
+		world.seed(), world.tick(), world.population());
+	for agent in world.agents() {
+		s += &format!("{:#?}\n\n", agent.brain)
+	}
+
+	// Archive the brain of the largest survivor
+	if let Some(champion) = world.agents().last() {
+		let genome = Genome::of(&champion.brain);
+
+		fs::write(args.out_dir.join("champion.json"),   genome.to_json())?;
+		fs::write(args.out_dir.join("champion.genome"), genome.to_bytes())?;
 	}
+
+	fs::write(args.out_dir.join("config.toml"), world.config().to_toml())?;
+	fs::write(args.out_dir.join("stats.csv"),   world.stats().to_csv())?;
+	fs::write(args.out_dir.join("stats.json"),  world.stats().to_json())?;
+
+	fs::write(args.out_dir.join("lineage.nwk"),  world.lineage().to_newick())?;
+	fs::write(args.out_dir.join("lineage.json"), world.lineage().to_json())?;
+	fs::write(args.out_dir.join("species.json"), world.species().to_json())?;
+	fs::write(args.out_dir.join("hall_of_fame.json"), world.hall_of_fame().to_json())?;
+	fs::write(args.out_dir.join("snapshot.json"), world.save_snapshot())?;
+	fs::write(args.out_dir.join("population.txt"), s)
 }
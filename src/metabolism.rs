@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::agent::*;
+
+/// An explicit energy store, separate from body size. Eating fills it, living drains it,
+/// and surplus energy is turned into growth. Once the store runs dry, the body is burnt
+/// instead, until the agent shrinks below `SimConfig::death_size` and starves.
+/// Energy is measured in units of body area (size²), like the food an agent eats.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetabolismConfig {
+	pub enabled: bool, // otherwise size is energy, shrinking by `SimConfig::shrink_factor`
+
+	pub birth_energy : f64, // energy of spawned agents, per unit of area
+	pub reserve      : f64, // energy stored per unit of area before the surplus is used for growth
+	pub digestion    : f64, // energy gained per unit of area eaten, on top of the prey's store
+
+	pub basal_cost     : f64, // per tick & unit of area
+	pub mov_cost       : f64, // per tick & unit of area at full speed
+	pub rot_cost       : f64, // per tick & unit of area at full rotation speed
+	pub neuron_cost    : f64, // per neuron fired
+	pub signal_cost    : f64, // per signal sent through a connection
+	pub growth_cost    : f64, // per unit of area grown, also gained per unit of area burnt
+	pub offspring_cost : f64  // per unit of area of a child, on top of the energy it is given
+}
+
+impl Default for MetabolismConfig {
+	fn default() -> Self {
+		MetabolismConfig {
+			enabled: false,
+
+			birth_energy : 0.5,
+			reserve      : 1.0,
+			digestion    : 1.0,
+
+			basal_cost     : 0.0002,
+			mov_cost       : 0.0002,
+			rot_cost       : 0.00005,
+			neuron_cost    : 0.01,
+			signal_cost    : 0.002,
+			growth_cost    : 1.0,
+			offspring_cost : 0.2
+		}
+	}
+}
+
+/// Neurons fired & signals sent by a brain during one tick
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Activity {
+	pub fired   : usize,
+	pub signals : usize
+}
+
+/// Energy given to an agent spawned from nothing
+pub fn birth_energy(size: f64, cfg: &MetabolismConfig) -> f64 {
+	match cfg.enabled {
+		true => cfg.birth_energy * size*size,
+		_    => 0.0
+	}
+}
+
+/// Pays for one tick of thinking
+pub fn think(body: &mut Body, activity: Activity, cfg: &MetabolismConfig) {
+	body.energy -= cfg.neuron_cost*activity.fired as f64 + cfg.signal_cost*activity.signals as f64
+}
+
+/// Pays for one tick of living & moving, then grows from any surplus energy,
+/// or burns the body to cover a deficit
+pub fn live(agent: &mut Agent, cfg: &MetabolismConfig) {
+	let body = &mut agent.body;
+	let area = body.size*body.size;
+
+	let mov = body.mov.abs().min(1.0);
+	let rot = body.rot.abs().min(1.0);
+
+	body.energy -= area * (cfg.basal_cost + cfg.mov_cost*mov + cfg.rot_cost*rot);
+
+	let surplus = match body.energy < 0.0 {
+		true => body.energy,
+		_    => (body.energy - cfg.reserve*area).max(0.0)
+	};
+	if surplus == 0.0 {return}
+
+	let new_size = (area + surplus/cfg.growth_cost).max(0.0).sqrt();
+
+	body.size    = new_size;
+	body.energy -= surplus;
+
+	agent.achievements.max_size = agent.achievements.max_size.max(new_size)
+}
+
+/// Gains the energy of an eaten agent
+pub fn digest(eater: &mut Body, prey_size: f64, prey_energy: f64, cfg: &MetabolismConfig) {
+	eater.energy += cfg.digestion*prey_size*prey_size + prey_energy.max(0.0)
+}
+
+/// Takes a child's share of the energy of a parent that just gave `child_size` of its body
+/// to it, in proportion to the area taken, and makes the parent pay for producing it
+pub fn endow(parent: &mut Body, child_size: f64, cfg: &MetabolismConfig) -> f64 {
+	if !cfg.enabled {return 0.0}
+
+	let (area, child_area) = (parent.size*parent.size, child_size*child_size);
+	let share = parent.energy.max(0.0) * child_area/(area + child_area);
+
+	parent.energy -= share + cfg.offspring_cost*child_area;
+	share
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+use crate::agent::{Neuron, OutwardConn};
+
+/// Firing rule of a neuron. Each tick a neuron first `integrate`s its accumulated excitation,
+/// then sends a `signal` through each outgoing connection if it `fires`, and finally `settle`s.
+pub trait NeuronModel {
+	/// Updates internal state from the excitation received since the last tick
+	fn integrate(&self, _neuron: &mut Neuron) {}
+
+	fn fires(&self, neuron: &Neuron) -> bool;
+
+	/// What a firing neuron adds to the excitation of the receiver of `conn`
+	fn signal(&self, neuron: &Neuron, conn: &OutwardConn) -> f64;
+
+	/// Leaks, drains or resets excitation after the neuron has been updated
+	fn settle(&self, neuron: &mut Neuron, fired: bool);
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelKind {
+	#[default]
+	Threshold,
+	LeakyIntegrateFire,
+	Izhikevich,
+	Sigmoid,
+	Tanh
+}
+
+impl ModelKind {
+	pub const ALL: [ModelKind; 5] = [
+		ModelKind::Threshold,
+		ModelKind::LeakyIntegrateFire,
+		ModelKind::Izhikevich,
+		ModelKind::Sigmoid,
+		ModelKind::Tanh
+	];
+
+	pub fn model(self) -> &'static dyn NeuronModel {
+		match self {
+			ModelKind::Threshold          => &Threshold,
+			ModelKind::LeakyIntegrateFire => &LeakyIntegrateFire,
+			ModelKind::Izhikevich         => &Izhikevich,
+			ModelKind::Sigmoid            => &Sigmoid,
+			ModelKind::Tanh               => &Tanh
+		}
+	}
+}
+
+////////////////////////////////
+
+/// The original rule: fire at `act_threshold`, drain towards 0 by `tick_drain` every tick.
+/// ReLU connections pass on the excitation scaled by their weight, others just their weight.
+pub struct Threshold;
+
+impl NeuronModel for Threshold {
+	fn fires(&self, neuron: &Neuron) -> bool {neuron.excitation >= neuron.act_threshold}
+
+	fn signal(&self, neuron: &Neuron, conn: &OutwardConn) -> f64 {
+		if conn.relu {conn.weight * neuron.excitation} else {conn.weight}
+	}
+
+	fn settle(&self, neuron: &mut Neuron, _fired: bool) {neuron.drain()}
+}
+
+/// Like `Threshold`, but resets to 0 after firing and otherwise leaks a
+/// fraction of its excitation every tick, the larger `tick_drain` the faster
+pub struct LeakyIntegrateFire;
+
+impl NeuronModel for LeakyIntegrateFire {
+	fn fires(&self, neuron: &Neuron) -> bool {neuron.excitation >= neuron.act_threshold}
+
+	fn signal(&self, neuron: &Neuron, conn: &OutwardConn) -> f64 {Threshold.signal(neuron, conn)}
+
+	fn settle(&self, neuron: &mut Neuron, fired: bool) {
+		if fired {
+			neuron.excitation = 0.0
+		} else {
+			let leak = neuron.tick_drain.abs() / (1.0 + neuron.tick_drain.abs());
+			neuron.excitation -= leak * neuron.excitation
+		}
+	}
+}
+
+/// Izhikevich's regular spiking neuron, with excitation as input current.
+/// `state` holds the membrane potential (mV) & recovery variable.
+pub struct Izhikevich;
+
+impl Izhikevich {
+	const A: f64 = 0.02;
+	const B: f64 = 0.2;
+	const C: f64 = -65.0;
+	const D: f64 = 8.0;
+
+	const PEAK  : f64 = 30.0;
+	const FLOOR : f64 = -100.0; // lowest membrane potential, far below rest
+
+	const GAIN        : f64 = 10.0;  // input current per unit of excitation
+	const MAX_CURRENT : f64 = 100.0; // input current is clamped to this either way
+}
+
+impl NeuronModel for Izhikevich {
+	fn integrate(&self, neuron: &mut Neuron) {
+		let [v, u] = &mut neuron.state;
+
+		// Start over from rest if the state ever ran off, e.g. in an older snapshot
+		if !v.is_finite() || !u.is_finite() {
+			*v = Izhikevich::C;
+			*u = Izhikevich::B * Izhikevich::C
+		}
+
+		// Reset after a spike
+		if *v >= Izhikevich::PEAK {
+			*v  = Izhikevich::C;
+			*u += Izhikevich::D
+		}
+
+		// Runaway excitation would otherwise blow up the state to infinity & NaN
+		let excitation = if neuron.excitation.is_finite() {neuron.excitation} else {0.0};
+		let current    = (Izhikevich::GAIN * excitation).clamp(-Izhikevich::MAX_CURRENT, Izhikevich::MAX_CURRENT);
+
+		// Two half-steps for numerical stability
+		for _ in 0..2 {
+			*v += 0.5 * (0.04*(*v)*(*v) + 5.0*(*v) + 140.0 - *u + current);
+			*v  = v.clamp(Izhikevich::FLOOR, Izhikevich::PEAK)
+		}
+		*u += Izhikevich::A * (Izhikevich::B*(*v) - *u);
+
+		neuron.excitation = 0.0
+	}
+
+	fn fires(&self, neuron: &Neuron) -> bool {neuron.state[0] >= Izhikevich::PEAK}
+
+	fn signal(&self, _neuron: &Neuron, conn: &OutwardConn) -> f64 {conn.weight}
+
+	fn settle(&self, _neuron: &mut Neuron, _fired: bool) {}
+}
+
+/// Classic rate-based unit, always passing on sigmoid(excitation - act_threshold)
+/// scaled by the weight, with no memory between ticks
+pub struct Sigmoid;
+
+impl NeuronModel for Sigmoid {
+	fn fires(&self, _neuron: &Neuron) -> bool {true}
+
+	fn signal(&self, neuron: &Neuron, conn: &OutwardConn) -> f64 {
+		conn.weight / (1.0 + libm::exp(neuron.act_threshold - neuron.excitation))
+	}
+
+	fn settle(&self, neuron: &mut Neuron, _fired: bool) {neuron.excitation = 0.0}
+}
+
+/// Like `Sigmoid`, but with tanh for signed output
+pub struct Tanh;
+
+impl NeuronModel for Tanh {
+	fn fires(&self, _neuron: &Neuron) -> bool {true}
+
+	fn signal(&self, neuron: &Neuron, conn: &OutwardConn) -> f64 {
+		conn.weight * libm::tanh(neuron.excitation - neuron.act_threshold)
+	}
+
+	fn settle(&self, neuron: &mut Neuron, _fired: bool) {neuron.excitation = 0.0}
+}
@@ -1,14 +1,61 @@
-use crate::agent::Neuron;
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::*, config::SimConfig};
+
+/// What each output neuron controls, in order. Only the first two are always present.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Output {
+	Mov,
+	Rot,
+	Split,     // split off a child when positive, see `SimConfig::split_output`
+	ChildRatio // child size relative to parent size, see `SimConfig::child_ratio_output`
+}
+
+impl Output {
+	pub(crate) const ALL: [Output; 4] = [Output::Mov, Output::Rot, Output::Split, Output::ChildRatio];
+
+	fn enabled(self, cfg: &SimConfig) -> bool {
+		match self {
+			Output::Mov | Output::Rot => true,
+			Output::Split             => cfg.split_output,
+			Output::ChildRatio        => cfg.child_ratio_output
+		}
+	}
+}
+
+/// The outputs enabled by the config, in neuron order
+pub fn layout(cfg: &SimConfig) -> Vec<Output> {
+	Output::ALL.into_iter().filter(|output| output.enabled(cfg)).collect()
+}
+
+/// Assigns every output neuron's response to what it controls
+pub fn assign_all(body: &mut Body, brain: &Brain, cfg: &SimConfig) {
+	for (neuron, output) in brain.neurons_out.iter().zip(&brain.output_kinds) {
+		let out = match output {
+			Output::Mov        => &mut body.mov,
+			Output::Rot        => &mut body.rot,
+			Output::Split      => &mut body.split,
+			Output::ChildRatio => &mut body.child_ratio
+		};
+
+		assign(out, neuron, cfg)
+	}
+}
+
+/// Sums up what a firing output neuron sends through its connections.
+/// Runaway sums count as 0, as NaN would otherwise get stuck in the body for good.
+pub fn assign(out: &mut f64, neuron: &Neuron, cfg: &SimConfig) {
+	let model = neuron.model(cfg);
 
-pub fn assign(out: &mut f64, neuron: &Neuron) {
 	*out = 0.0;
-	if neuron.excitation >= neuron.act_threshold {
+	if model.fires(neuron) {
 		for conn in &neuron.next_conn {
-			if conn.relu {
-				*out += conn.weight * neuron.excitation
-			} else {
-				*out += conn.weight
-			}
+			*out += model.signal(neuron, conn)
 		}
 	}
+
+	if !out.is_finite() {
+		*out = 0.0
+	}
 }
@@ -0,0 +1,145 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::*, config::SimConfig, spatial::Grid};
+
+/// Sensing several agents around at once, rather than just the nearest one
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerceptionConfig {
+	pub neighbours : usize, // nearest agents sensed individually, 0 to disable
+	pub sectors    : usize, // angular sectors around the agent sensing their nearest agent, 0 to disable
+
+	pub range: f64 // how far agents can see, centre to centre
+}
+
+impl Default for PerceptionConfig {
+	fn default() -> Self {
+		PerceptionConfig {
+			neighbours : 0,
+			sectors    : 0,
+
+			range: 200.0
+		}
+	}
+}
+
+/// What can be sensed about another agent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sense {
+	RelSize, // positive if smaller, negative if larger
+	Dist,    // closeness, 1 when touching & 0 at the edge of the range
+	Bearing, // angle relative to heading
+	Closing  // speed at which the distance shrinks
+}
+
+impl Sense {
+	pub(crate) const ALL: [Sense; 4] = [Sense::RelSize, Sense::Dist, Sense::Bearing, Sense::Closing];
+}
+
+/// Another agent as seen by one, normalised to [-1, 1]
+#[derive(Clone, Copy, Debug)]
+pub struct Seen {
+	rel_size : f64,
+	dist     : f64,
+	bearing  : f64,
+	closing  : f64
+}
+
+/// Everything seen by one agent in a tick
+#[derive(Default)]
+pub struct Percept {
+	pub neighbours : Vec<Seen>,         // nearest first
+	pub sectors    : Vec<Option<Seen>>  // clockwise, starting straight ahead
+}
+
+////////////////////////////////
+
+impl Percept {
+	pub fn of(agents: &[Agent], i: usize, grid: &Grid, cfg: &SimConfig) -> Percept {
+		let (k, n, range) = (cfg.perception.neighbours, cfg.perception.sectors, cfg.perception.range);
+		if k == 0 && n == 0 {
+			return Percept::default()
+		}
+
+		let body = &agents[i].body;
+
+		// Everything within range, nearest first (ties going to the lowest index)
+		let mut candidates = vec![];
+		grid.overlapping(body.pos, range, &mut candidates);
+
+		let mut in_range: Vec<_> = candidates.into_iter()
+			.filter(|&j| j != i)
+			.map(|j| (body.pos.dist(agents[j].body.pos, cfg), j))
+			.filter(|&(dist, _)| dist <= range)
+			.collect();
+		in_range.sort_unstable_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+		let neighbours = in_range.iter().take(k)
+			.map(|&(dist, j)| Seen::of(body, &agents[j].body, dist, cfg))
+			.collect();
+
+		let mut sectors = vec![None; n];
+		if n > 0 {
+			for &(dist, j) in &in_range {
+				let seen   = Seen::of(body, &agents[j].body, dist, cfg);
+				let sector = &mut sectors[seen.sector(n)];
+
+				if sector.is_none() {
+					*sector = Some(seen)
+				}
+			}
+		}
+
+		Percept {neighbours, sectors}
+	}
+}
+
+impl Seen {
+	fn of(me: &Body, other: &Body, dist: f64, cfg: &SimConfig) -> Seen {
+		let (dx, dy) = me.pos.delta(other.pos, cfg);
+
+		let bearing = norm_angle(libm::atan2(dy, dx) - me.angle);
+
+		// Relative velocity along the line between both
+		let ((vx1, vy1), (vx2, vy2)) = (velocity(me, cfg), velocity(other, cfg));
+		let closing = match dist > 0.0 {
+			true => -((vx2 - vx1)*dx + (vy2 - vy1)*dy) / dist,
+			_    => 0.0
+		};
+
+		Seen {
+			rel_size : (me.size - other.size) / (me.size + other.size),
+			dist     : 1.0 - dist / cfg.perception.range,
+			bearing  : bearing / PI,
+			closing  : (closing / (2.0*cfg.mov_speed)).clamp(-1.0, 1.0)
+		}
+	}
+
+	pub fn sense(&self, sense: Sense) -> f64 {
+		match sense {
+			Sense::RelSize => self.rel_size,
+			Sense::Dist    => self.dist,
+			Sense::Bearing => self.bearing,
+			Sense::Closing => self.closing
+		}
+	}
+
+	// Sector 0 is centred straight ahead
+	fn sector(&self, n: usize) -> usize {
+		let turn = (self.bearing/2.0 + 0.5/n as f64).rem_euclid(1.0); // fraction of a full turn
+		(turn * n as f64) as usize % n
+	}
+}
+
+fn norm_angle(angle: f64) -> f64 {
+	libm::atan2(libm::sin(angle), libm::cos(angle)) // within [-PI, PI]
+}
+
+// Distance moved per tick, as in `game::mov`
+fn velocity(body: &Body, cfg: &SimConfig) -> (f64, f64) {
+	let speed = cfg.mov_speed * body.mov.clamp(-1.0, 1.0);
+	(speed * libm::cos(body.angle), speed * libm::sin(body.angle))
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+use crate::agent::*;
+
+/// Spike-timing-dependent plasticity, letting brains adapt their weights during their lifetime.
+/// How strongly each neuron's outgoing connections adapt is scaled by its evolved `learn_rate`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlasticityConfig {
+	pub enabled: bool,
+
+	pub window : u64, // max ticks between pre- & postsynaptic spikes for any change
+	pub tau    : f64, // time constant of the exponential decay within the window
+
+	pub a_plus  : f64, // strengthening when the receiver fires after the sender
+	pub a_minus : f64, // weakening when the receiver fires before the sender
+
+	pub min_weight : f64, // bounds on weight magnitude, so that plasticity alone never flips or
+	pub max_weight : f64  // removes a connection; evolved weights beyond them are never pushed further out
+}
+
+impl Default for PlasticityConfig {
+	fn default() -> Self {
+		PlasticityConfig {
+			enabled: false,
+
+			window : 4,
+			tau    : 2.0,
+
+			a_plus  : 1.0,
+			a_minus : 1.0,
+
+			min_weight : 1.0,
+			max_weight : 8.0
+		}
+	}
+}
+
+/// Adjusts spiking (non-ReLU) connections based on when their ends last fired.
+/// Called once per tick, after all neurons of the brain have been updated.
+pub fn apply(brain: &mut Brain, cfg: &PlasticityConfig) {
+	let now = brain.clock;
+
+	let last_fired = |brain: &Brain, dest_index: usize| {
+		let outs = brain.neurons_out.len();
+
+		if dest_index < outs {
+			brain.neurons_out[dest_index].last_fired
+		} else {
+			brain.neurons_hid[dest_index - outs].last_fired
+		}
+	};
+
+	let inps = brain.neurons_inp.len();
+
+	for i in 0..inps + brain.neurons_hid.len() {
+		let sender = if i < inps {&brain.neurons_inp[i]} else {&brain.neurons_hid[i - inps]};
+
+		let (Some(sent), learn_rate) = (sender.last_fired, sender.learn_rate) else {continue};
+		if learn_rate == 0.0 {continue}
+
+		let changes: Vec<_> = sender.next_conn.iter().map(|conn| {
+			let Some(received) = last_fired(brain, conn.dest_index) else {return 0.0};
+			if conn.relu {return 0.0}
+
+			// Only act on the tick either end fires, so each spike pair counts once
+			if sent != now && received != now {return 0.0}
+
+			// Spikes arrive `speed` ticks after being sent
+			let dt = received as f64 - (sent + conn.speed as u64) as f64;
+			if dt.abs() > cfg.window as f64 {return 0.0}
+
+			if dt >= 0.0 && received == now {
+				// Receiver firing after this => strengthen connection
+				learn_rate * cfg.a_plus * libm::exp(-dt / cfg.tau)
+			} else if dt < 0.0 && sent == now {
+				// Receiver already has fired => weaken connection
+				-learn_rate * cfg.a_minus * libm::exp(dt / cfg.tau)
+			} else {0.0}
+		}).collect();
+
+		let sender = if i < inps {&mut brain.neurons_inp[i]} else {&mut brain.neurons_hid[i - inps]};
+		for (conn, change) in sender.next_conn.iter_mut().zip(changes) {
+			if change != 0.0 {
+				// Only the change is bounded, an evolved weight beyond the bounds is kept as is
+				let magnitude = conn.weight.abs();
+				let magnitude = (magnitude + change).clamp(cfg.min_weight.min(magnitude), cfg.max_weight.max(magnitude));
+
+				conn.weight = magnitude.copysign(conn.weight)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{agent::tests::*, output::Output};
+
+	// Weight of a spiking connection after the sender & receiver last fired at the given ticks
+	fn weight_after(weight: f64, speed: usize, sent: u64, received: u64) -> f64 {
+		let mut sender = neuron(vec![OutwardConn {weight, relu: false, ..conn(0, speed)}]);
+		sender.learn_rate = 1.0;
+		sender.last_fired = Some(sent);
+
+		let mut brain = brain(vec![sender], vec![], vec![Output::Mov]);
+		brain.neurons_out[0].last_fired = Some(received);
+		brain.clock = sent.max(received);
+
+		apply(&mut brain, &PlasticityConfig {enabled: true, ..PlasticityConfig::default()});
+		brain.neurons_inp[0].next_conn[0].weight
+	}
+
+	fn close(a: f64, b: f64) -> bool {(a - b).abs() < 1e-12}
+
+	#[test]
+	fn pre_before_post_strengthens() {
+		assert!(close(weight_after(2.0, 0, 5, 6), 2.0 + libm::exp(-0.5)));
+		assert!(close(weight_after(-2.0, 0, 5, 6), -2.0 - libm::exp(-0.5)))
+	}
+
+	#[test]
+	fn post_before_pre_weakens() {
+		assert!(close(weight_after(2.0, 0, 6, 5), 2.0 - libm::exp(-0.5)));
+		assert_eq!(weight_after(2.0, 0, 6, 1), 2.0) // outside the window
+	}
+
+	#[test]
+	fn delayed_spikes_count_from_their_arrival() {
+		// Arriving just as the receiver fires
+		assert_eq!(weight_after(2.0, 2, 4, 6), 3.0);
+
+		// Sent as the receiver fires, so arriving after it
+		assert!(close(weight_after(2.0, 2, 6, 6), 2.0 - libm::exp(-1.0)))
+	}
+
+	#[test]
+	fn changes_stay_within_the_bounds() {
+		assert_eq!(weight_after(7.9, 0, 5, 5), 8.0);
+		assert_eq!(weight_after(1.1, 0, 5, 4), 1.0);
+
+		// Evolved weights beyond the bounds are not pulled back in
+		assert_eq!(weight_after(12.0, 0, 5, 5), 12.0);
+		assert_eq!(weight_after(0.5, 0, 5, 4), 0.5);
+		assert!(close(weight_after(12.0, 0, 5, 4), 12.0 - libm::exp(-0.5)))
+	}
+}
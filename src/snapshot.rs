@@ -0,0 +1,238 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{config::SimConfig, input::Input, output::Output, world::World};
+
+/// Bump whenever the serialised layout of `World` changes,
+/// and add a migration from the previous version to `load`
+pub const SNAPSHOT_VERSION: u64 = 6;
+
+#[derive(Serialize)]
+struct Snapshot<'a> {
+	version : u64,
+	world   : &'a World
+}
+
+pub fn save(world: &World) -> String {
+	serde_json::to_string(&Snapshot {version: SNAPSHOT_VERSION, world})
+		.expect("world is always serialisable")
+}
+
+pub fn load(s: &str) -> Result<World, String> {
+	let mut snapshot: Value = serde_json::from_str(s).map_err(|e| format!("invalid snapshot: {e}"))?;
+
+	let version = snapshot["version"].as_u64().ok_or("invalid snapshot: missing version")?;
+	if version > SNAPSHOT_VERSION {
+		return Err(format!(
+			"snapshot version {version} is newer than supported version {SNAPSHOT_VERSION}"
+		))
+	}
+
+	let world = &mut snapshot["world"];
+	if version < 2 {
+		migrate_v1(world)
+	}
+	if version < 3 {
+		migrate_v2(world)
+	}
+	if version < 4 {
+		migrate_v3(world)
+	}
+	if version < 5 {
+		SimConfig::upgrade(&mut world["config"]) // v5 split `game_size` into width & height
+	}
+	if version < 6 {
+		let mut next = 0;
+		migrate_v5(world, &mut next);
+		world["innovations"] = json!({"next": next})
+	}
+
+	let world: World = serde_json::from_value(world.take()).map_err(|e| format!("invalid snapshot: {e}"))?;
+
+	// A snapshot may have been edited by hand, so check what would otherwise panic later
+	world.config().clone().validated().map_err(|e| format!("invalid snapshot: {e}"))?;
+
+	let brains = world.agents().iter().map(|agent| &agent.brain)
+		.chain(world.hall_of_fame().entries().iter().map(|entry| &entry.brain));
+	for brain in brains {
+		brain.validate().map_err(|e| format!("invalid snapshot: {e}"))?
+	}
+
+	Ok(world)
+}
+
+// v2 added agent ids & lineage; give the agents alive at the time fresh ids as founders
+fn migrate_v1(world: &mut Value) {
+	let tick = world["tick"].clone();
+
+	let mut records = vec![];
+	if let Some(agents) = world["agents"].as_array_mut() {
+		for (id, agent) in agents.iter_mut().enumerate() {
+			agent["id"]     = json!(id);
+			agent["parent"] = Value::Null;
+
+			records.push(json!({
+				"id"         : id,
+				"parent"     : null,
+				"generation" : agent["brain"]["generation"],
+				"born"       : tick,
+				"died"       : null,
+				"cause"      : null
+			}))
+		}
+	}
+
+	world["lineage"] = json!({"ids": {"next": records.len()}, "records": records})
+}
+
+// v3 labelled the inputs & outputs of brains, which used to be fixed
+fn migrate_v2(value: &mut Value) {
+	match value {
+		Value::Object(object) => {
+			if object.contains_key("neurons_inp") && !object.contains_key("input_kinds") {
+				let count = |key: &str| object[key].as_array().map_or(0, Vec::len);
+
+				let input_kinds : Vec<_> = Input::LEGACY.iter().take(count("neurons_inp")).collect();
+				let output_kinds: Vec<_> = Output::ALL.iter().take(count("neurons_out")).collect();
+
+				object.insert("input_kinds".into(),  json!(input_kinds));
+				object.insert("output_kinds".into(), json!(output_kinds));
+			}
+
+			object.values_mut().for_each(migrate_v2)
+		}
+
+		Value::Array(values) => values.iter_mut().for_each(migrate_v2),
+
+		_ => {}
+	}
+}
+
+// v4 made bodies circles centred on their position, which used to be the top-left corner of a square
+fn migrate_v3(world: &mut Value) {
+	for agent in world["agents"].as_array_mut().into_iter().flatten() {
+		let body = &mut agent["body"];
+		let half = body["size"].as_f64().unwrap_or(0.0) / 2.0;
+
+		for axis in ["x", "y"] {
+			if let Some(coord) = body["pos"][axis].as_f64() {
+				body["pos"][axis] = json!(coord + half)
+			}
+		}
+	}
+}
+
+// v6 added innovation ids; without a record of shared ancestry, every gene gets its own
+fn migrate_v5(value: &mut Value, next: &mut u64) {
+	let mut number = |gene: &mut Value| {
+		*next += 1;
+		gene["innovation"] = json!(*next)
+	};
+
+	match value {
+		Value::Object(object) => {
+			if object.contains_key("neurons_inp") {
+				for key in ["neurons_inp", "neurons_hid", "neurons_out"] {
+					for neuron in object[key].as_array_mut().into_iter().flatten() {
+						number(neuron);
+						neuron["next_conn"].as_array_mut().into_iter().flatten().for_each(&mut number)
+					}
+				}
+			}
+
+			object.values_mut().for_each(|value| migrate_v5(value, next))
+		}
+
+		Value::Array(values) => values.iter_mut().for_each(|value| migrate_v5(value, next)),
+
+		_ => {}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn world_after(ticks: u64, config: SimConfig, seed: u64) -> World {
+		let mut world = World::with_config(config, Some(seed));
+		for _ in 0..ticks {
+			world.step()
+		}
+		world
+	}
+
+	#[test]
+	fn save_load_save_is_lossless() {
+		let world = world_after(3000, SimConfig::default(), 1);
+		let saved = save(&world);
+
+		assert_eq!(save(&load(&saved).unwrap()), saved)
+	}
+
+	#[test]
+	fn save_load_save_with_evolved_neuron_models() {
+		// Izhikevich neurons used to run off to NaN here, which was saved as null
+		let config = SimConfig {evolve_neuron_model: true, ..SimConfig::default()};
+		let saved  = save(&world_after(3000, config, 1));
+
+		assert_eq!(save(&load(&saved).unwrap()), saved)
+	}
+
+	#[test]
+	fn loaded_world_carries_on_identically() {
+		let mut world  = world_after(2000, SimConfig::default(), 2);
+		let mut loaded = load(&save(&world)).unwrap();
+
+		for _ in 0..1000 {
+			world.step();
+			loaded.step()
+		}
+
+		assert_eq!(save(&loaded), save(&world))
+	}
+
+	#[test]
+	fn loads_v1_snapshot() {
+		let mut world = load(include_str!("../tests/fixtures/snapshot-v1.json")).unwrap();
+
+		assert_eq!(world.tick(), 120);
+		assert_eq!(world.config().width,  600.0);
+		assert_eq!(world.config().height, 600.0);
+
+		// Founders of the lineage, with positions moved from the corner to the centre
+		let ids: Vec<_> = world.agents().iter().map(|agent| agent.id).collect();
+		assert_eq!(ids, [0, 1]);
+		assert_eq!(world.lineage().records().len(), 2);
+
+		let body = &world.agents()[0].body;
+		assert_eq!((body.pos.x, body.pos.y), (161.67456880711728 + body.size/2.0, body.size/2.0));
+
+		let saved = save(&world);
+		assert_eq!(save(&load(&saved).unwrap()), saved);
+
+		for _ in 0..100 {
+			world.step()
+		}
+	}
+
+	#[test]
+	fn rejects_snapshots_that_would_panic() {
+		let saved: Value = serde_json::from_str(&save(&world_after(500, SimConfig::default(), 3))).unwrap();
+
+		let mut snapshot = saved.clone();
+		snapshot["world"]["config"]["inverse_spawn_rate"] = json!(0);
+		assert!(load(&snapshot.to_string()).err().unwrap().contains("inverse_spawn_rate"));
+
+		let mut snapshot = saved;
+		snapshot["world"]["agents"][0]["brain"]["neurons_inp"][0]["next_conn"][0]["dest_index"] = json!(9999);
+		assert!(load(&snapshot.to_string()).err().unwrap().contains("connection to #9999"))
+	}
+
+	#[test]
+	fn rejects_newer_or_unversioned_snapshots() {
+		let newer = format!(r#"{{"version": {}, "world": {{}}}}"#, SNAPSHOT_VERSION + 1);
+
+		assert!(load(&newer).err().unwrap().contains("newer"));
+		assert!(load(r#"{"world": {}}"#).err().unwrap().contains("missing version"))
+	}
+}
@@ -0,0 +1,211 @@
+use std::{iter::Chain, ops::Range};
+
+use crate::{agent::*, config::SimConfig};
+
+/// Uniform grid over the game area, rebuilt every tick, so that collisions &
+/// nearest-neighbour searches only have to look at agents in nearby cells.
+/// Each agent is registered in every cell its body overlaps, across the edges if the world wraps.
+#[derive(Default)]
+pub struct Grid {
+	cols: Axis,
+	rows: Axis,
+
+	wrap: bool,
+
+	cells: Vec<Vec<usize>>
+}
+
+// Division of the width or height of the game area into cells
+#[derive(Default)]
+struct Axis {
+	cell_size : f64,
+	len       : usize, // number of cells
+	extent    : f64
+}
+
+impl Grid {
+	pub fn rebuild(&mut self, agents: &[Agent], cfg: &SimConfig) {
+		self.rebuild_from(agents.iter().map(|agent| (agent.body.pos, agent.body.radius())), cfg)
+	}
+
+	/// Like `rebuild`, for anything else given by its centre & radius, e.g. food pellets
+	pub fn rebuild_from(&mut self, items: impl Iterator<Item = (Pos, f64)>, cfg: &SimConfig) {
+		self.cols = Axis::new(cfg.width,  cfg.grid_cell_size, cfg.wrap);
+		self.rows = Axis::new(cfg.height, cfg.grid_cell_size, cfg.wrap);
+		self.wrap = cfg.wrap;
+
+		let cols = self.cols.len;
+		self.cells.resize_with(cols * self.rows.len, Vec::new);
+		self.cells.truncate(cols * self.rows.len);
+		for cell in &mut self.cells {
+			cell.clear()
+		}
+
+		for (i, (pos, r)) in items.enumerate() {
+			for row in self.rows.span(pos.y - r, pos.y + r, self.wrap) {
+				for col in self.cols.span(pos.x - r, pos.x + r, self.wrap) {
+					self.cells[row*cols + col].push(i)
+				}
+			}
+		}
+	}
+
+	/// Collects, in ascending order, every agent that may be within `radius` of `centre`
+	pub fn overlapping(&self, centre: Pos, radius: f64, found: &mut Vec<usize>) {
+		found.clear();
+
+		let cols = self.cols.span(centre.x - radius, centre.x + radius, self.wrap);
+		for row in self.rows.span(centre.y - radius, centre.y + radius, self.wrap) {
+			found.extend(cols.clone().flat_map(|col| &self.cells[row*self.cols.len + col]))
+		}
+
+		found.sort_unstable();
+		found.dedup()
+	}
+
+	/// Agents registered in the cells exactly `ring` cells away (Chebyshev) from the cell of `pos`.
+	/// Agents spanning several cells may be yielded more than once.
+	pub fn ring(&self, pos: Pos, ring: usize) -> impl Iterator<Item = usize> + '_ {
+		let (col, row) = (self.cols.cell(pos.x), self.rows.cell(pos.y));
+		let ring = ring as isize;
+
+		(-ring..=ring).flat_map(move |dr| (-ring..=ring).map(move |dc| (dc, dr)))
+			.filter(move |&(dc, dr)| dc.abs() == ring || dr.abs() == ring)
+			.filter_map(move |(dc, dr)| Some((self.cols.step(col, dc, self.wrap)?, self.rows.step(row, dr, self.wrap)?)))
+			.flat_map(move |(c, r)| self.cells[r*self.cols.len + c].iter().copied())
+	}
+
+	/// Number of rings needed to cover the whole grid from any cell
+	pub fn max_ring(&self) -> usize {
+		let cells = self.cols.len.max(self.rows.len);
+
+		match self.wrap {
+			true => cells/2 + 1,
+			_    => cells
+		}
+	}
+
+	/// Anything beyond `ring` rings away is at least this far away along some axis
+	pub fn min_dist_beyond(&self, ring: usize) -> f64 {
+		ring as f64 * self.cols.cell_size.min(self.rows.cell_size)
+	}
+}
+
+impl Axis {
+	fn new(extent: f64, cell_size: f64, wrap: bool) -> Axis {
+		let len = ((extent / cell_size).ceil() as usize).max(1);
+
+		// Wrapping around needs equally sized cells all the way
+		let cell_size = if wrap {extent / len as f64} else {cell_size};
+
+		Axis {cell_size, len, extent}
+	}
+
+	fn cell(&self, c: f64) -> usize {
+		((c / self.cell_size).max(0.0) as usize).min(self.len - 1)
+	}
+
+	// Cell index `offset` away from `i`, if not past the edge
+	fn step(&self, i: usize, offset: isize, wrap: bool) -> Option<usize> {
+		match wrap {
+			true => Some((i as isize + offset).rem_euclid(self.len as isize) as usize),
+			_    => usize::try_from(i as isize + offset).ok().filter(|&i| i < self.len)
+		}
+	}
+
+	// Cells covering [lo, hi], split in two where that crosses a wrapped edge
+	fn span(&self, lo: f64, hi: f64, wrap: bool) -> Chain<Range<usize>, Range<usize>> {
+		if !wrap {
+			return (self.cell(lo)..self.cell(hi) + 1).chain(0..0)
+		}
+		if hi - lo >= self.extent {
+			return (0..self.len).chain(0..0)
+		}
+
+		let width = hi - lo;
+		let lo    = lo.rem_euclid(self.extent);
+		let hi    = lo + width;
+		match hi < self.extent {
+			true => (self.cell(lo)..self.cell(hi) + 1).chain(0..0),
+			_    => (self.cell(lo)..self.len).chain(0..self.cell(hi - self.extent) + 1)
+		}
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use rand::SeedableRng;
+
+	use super::*;
+	use crate::{crossover::Innovations, helpers::*, lineage::AgentIds};
+
+	/// Scatters a random number of the given agents, of random sizes, across a random world,
+	/// wrapping around or not. Returns the config & the agents in use.
+	pub(crate) fn random_world<'a>(agents: &'a mut [Agent], rng: &mut SimRng) -> (SimConfig, &'a [Agent]) {
+		let cfg = SimConfig {
+			width          : rand_range(rng, 50.0..1000.0),
+			height         : rand_range(rng, 50.0..1000.0),
+			wrap           : rand_range(rng, 0..=1) == 0,
+			grid_cell_size : rand_range(rng, 8.0..200.0),
+			..SimConfig::default()
+		};
+
+		let count  = rand_range(rng, 1..=agents.len());
+		let agents = &mut agents[..count];
+		for agent in agents.iter_mut() {
+			agent.body.pos  = Pos::new(&cfg, rng);
+			agent.body.size = rand_range(rng, 2.0..150.0)
+		}
+
+		(cfg, agents)
+	}
+
+	pub(crate) fn population(rng: &mut SimRng) -> Vec<Agent> {
+		let (mut ids, mut innov) = (AgentIds::default(), Innovations::default());
+		(0..60).map(|_| Agent::new(&SimConfig::default(), &mut ids, &mut innov, rng)).collect()
+	}
+
+	#[test]
+	fn overlapping_finds_everything_in_range() {
+		let mut rng    = SimRng::seed_from_u64(7);
+		let mut agents = population(&mut rng);
+		let mut grid   = Grid::default();
+		let mut found  = vec![];
+
+		for _ in 0..400 {
+			let (cfg, agents) = random_world(&mut agents, &mut rng);
+			grid.rebuild(agents, &cfg);
+
+			let centre = Pos::new(&cfg, &mut rng);
+			let radius = rand_range(&mut rng, 0.0..300.0);
+			grid.overlapping(centre, radius, &mut found);
+
+			assert!(found.windows(2).all(|pair| pair[0] < pair[1]), "not ascending & unique: {found:?}");
+
+			for (i, agent) in agents.iter().enumerate() {
+				if centre.dist(agent.body.pos, &cfg) <= radius + agent.body.radius() {
+					assert!(found.contains(&i), "missed agent {i} within range in {cfg:?}")
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn rings_cover_the_whole_grid() {
+		let mut rng    = SimRng::seed_from_u64(8);
+		let mut agents = population(&mut rng);
+		let mut grid   = Grid::default();
+
+		for _ in 0..400 {
+			let (cfg, agents) = random_world(&mut agents, &mut rng);
+			grid.rebuild(agents, &cfg);
+
+			let pos = Pos::new(&cfg, &mut rng);
+			let mut seen: Vec<_> = (0..=grid.max_ring()).flat_map(|ring| grid.ring(pos, ring)).collect();
+			seen.sort_unstable();
+			seen.dedup();
+
+			assert_eq!(seen, (0..agents.len()).collect::<Vec<_>>(), "in {cfg:?}")
+		}
+	}
+}
@@ -0,0 +1,220 @@
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{agent::*, helpers::*};
+
+/// Grouping of the population into species of genetically similar brains, as in NEAT
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeciesConfig {
+	pub enabled  : bool,
+	pub interval : u64, // ticks between re-assigning agents to species
+
+	pub threshold: f64, // max distance to a species' representative to belong to it
+
+	pub conn_coefficient   : f64, // weight of the fraction of unmatched connections
+	pub weight_coefficient : f64, // weight of the mean weight difference of matching connections
+	pub hidden_coefficient : f64, // weight of the difference in hidden neuron count
+
+	// Agents of crowded species split less often, protecting new ones: their odds of splitting
+	// shrink, or with `SimConfig::split_output` their split cooldown grows, with the crowding
+	pub fitness_sharing: bool
+}
+
+impl Default for SpeciesConfig {
+	fn default() -> Self {
+		SpeciesConfig {
+			enabled  : false,
+			interval : 50,
+
+			threshold: 3.0,
+
+			conn_coefficient   : 1.0,
+			weight_coefficient : 0.5,
+			hidden_coefficient : 0.2,
+
+			fitness_sharing: false
+		}
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Species {
+	pub id: u64,
+
+	pub founded : u64,
+	pub extinct : Option<u64>,
+
+	pub colour: Colour, // of the founder
+
+	pub history: Vec<(u64, usize)>, // (tick, members) at every speciation
+
+	representative: Option<Brain> // dropped on extinction
+}
+
+/// Every species that has ever existed in a world
+#[derive(Default, Serialize, Deserialize)]
+pub struct Speciation {
+	next_id: u64,
+
+	species: Vec<Species> // ordered by id
+}
+
+/// Genetic distance between two brains, aligning their connections by innovation id
+/// like `crossover::crossover` does
+pub fn distance(a: &Brain, b: &Brain, cfg: &SpeciesConfig) -> f64 {
+	let genes = |brain: &Brain| {
+		let mut genes: Vec<(u64, f64)> = brain.neurons_inp.iter().chain(&brain.neurons_hid).chain(&brain.neurons_out)
+			.flat_map(|neuron| neuron.next_conn.iter().map(|conn| (conn.innovation, conn.weight)))
+			.collect();
+		genes.sort_unstable_by_key(|&(innovation, _)| innovation);
+		genes
+	};
+	let (xs, ys) = (genes(a), genes(b));
+
+	let (mut matching, mut weight_diff) = (0, 0.0);
+	let (mut i, mut j) = (0, 0);
+
+	while i < xs.len() && j < ys.len() {
+		match xs[i].0.cmp(&ys[j].0) {
+			std::cmp::Ordering::Less    => i += 1,
+			std::cmp::Ordering::Greater => j += 1,
+			std::cmp::Ordering::Equal   => {
+				matching    += 1;
+				weight_diff += (xs[i].1 - ys[j].1).abs();
+				i += 1;
+				j += 1
+			}
+		}
+	}
+	let unmatched = xs.len() + ys.len() - 2 * matching;
+
+	let conns = a.conn_count().max(b.conn_count()).max(1) as f64;
+
+	cfg.conn_coefficient   * unmatched as f64 / conns +
+	cfg.weight_coefficient * if matching > 0 {weight_diff / matching as f64} else {0.0} +
+	cfg.hidden_coefficient * a.hidden_count().abs_diff(b.hidden_count()) as f64
+}
+
+impl Speciation {
+	pub fn species(&self) -> &[Species] {&self.species}
+
+	pub fn get(&self, id: u64) -> Option<&Species> {
+		self.index_of(id).map(|i| &self.species[i])
+	}
+
+	/// Assigns every agent to the first species whose representative is close enough,
+	/// trying its current one first, or founds a new one. Afterwards picks a random
+	/// member as each species' new representative.
+	pub fn update(&mut self, agents: &mut [Agent], tick: u64, cfg: &SpeciesConfig, rng: &mut SimRng) {
+		let mut members = vec![vec![]; self.species.len()];
+
+		for (i, agent) in agents.iter_mut().enumerate() {
+			let current = agent.species.and_then(|id| self.index_of(id));
+
+			let close = |species: &Species| species.representative.as_ref()
+				.is_some_and(|brain| distance(brain, &agent.brain, cfg) < cfg.threshold);
+
+			let found = current.filter(|&k| close(&self.species[k]))
+				.or_else(|| self.species.iter().position(&close));
+
+			let k = found.unwrap_or_else(|| {
+				self.species.push(Species {
+					id: self.next_id,
+
+					founded : tick,
+					extinct : None,
+
+					colour: agent.body.colour.clone(),
+
+					history: vec![],
+
+					representative: Some(agent.brain.clone())
+				});
+				members.push(vec![]);
+
+				self.next_id += 1;
+				self.species.len() - 1
+			});
+
+			agent.species = Some(self.species[k].id);
+			members[k].push(i)
+		}
+
+		for (species, members) in self.species.iter_mut().zip(&members) {
+			if species.extinct.is_some() {continue}
+
+			match members.choose(rng) {
+				Some(&i) => {
+					species.history.push((tick, members.len()));
+					species.representative = Some(agents[i].brain.clone())
+				}
+				None => {
+					species.extinct        = Some(tick);
+					species.representative = None
+				}
+			}
+		}
+	}
+
+	/// Members of the given species relative to the mean species size, as of the last update
+	pub fn crowding(&self, id: Option<u64>) -> f64 {
+		let members = |species: &Species| match species.extinct {
+			None => species.history.last().map(|&(_, members)| members),
+			_    => None
+		};
+
+		let sizes: Vec<_> = self.species.iter().filter_map(members).collect();
+		let total: usize  = sizes.iter().sum();
+
+		match id.and_then(|id| self.get(id)).and_then(members) {
+			Some(members) if total > 0 => members as f64 * sizes.len() as f64 / total as f64,
+			_ => 1.0
+		}
+	}
+
+	pub fn to_json(&self) -> String {
+		let species: Vec<_> = self.species.iter().map(|s| json!({
+			"id"      : s.id,
+			"founded" : s.founded,
+			"extinct" : s.extinct,
+			"colour"  : s.colour,
+			"history" : s.history
+		})).collect();
+
+		serde_json::to_string_pretty(&species).expect("species are always serialisable")
+	}
+
+	fn index_of(&self, id: u64) -> Option<usize> {
+		self.species.binary_search_by_key(&id, |species| species.id).ok()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rand::SeedableRng;
+
+	use super::*;
+	use crate::{config::SimConfig, crossover::Innovations, lineage::AgentIds};
+
+	#[test]
+	fn distance_aligns_on_innovation_ids() {
+		let mut rng = SimRng::seed_from_u64(4);
+		let (mut ids, mut innov) = (AgentIds::default(), Innovations::default());
+		let cfg = SpeciesConfig::default();
+
+		let brain = Agent::new(&SimConfig::default(), &mut ids, &mut innov, &mut rng).brain;
+
+		// Only ids matter, not where genes sit
+		let mut shuffled = brain.clone();
+		shuffled.neurons_hid.reverse();
+		shuffled.neurons_hid.iter_mut().for_each(|neuron| neuron.next_conn.reverse());
+		assert_eq!(distance(&brain, &shuffled, &cfg), 0.0);
+
+		// The same genes evolved independently share nothing
+		let mut unrelated = brain.clone();
+		unrelated.renumber(&mut innov);
+		assert_eq!(distance(&brain, &unrelated, &cfg), 2.0 * cfg.conn_coefficient)
+	}
+}
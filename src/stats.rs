@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+use crate::agent::*;
+
+/// Population statistics at one point in time.
+/// Event counts are totals since the previous sample.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sample {
+	pub tick   : u64,
+	pub agents : usize,
+
+	pub mean_size : f64,
+	pub max_size  : f64,
+
+	pub mean_generation : f64,
+	pub max_generation  : usize,
+
+	pub mean_hidden_neurons : f64,
+	pub mean_connections    : f64,
+	pub reachable_ratio     : f64, // of hidden neurons
+
+	#[serde(default)]
+	pub species: usize, // with members alive, 0 unless speciation is enabled
+
+	pub births  : usize, // children split off from parents
+	#[serde(default)]
+	pub matings : usize, // children of two parents
+	pub spawns  : usize, // randomly spawned agents
+	pub starved : usize,
+	pub eaten   : usize,
+	#[serde(default)]
+	pub foraged : usize, // pellets eaten
+
+	pub mean_inv_split_freq: f64
+}
+
+/// Time-series recorder, sampling the population every `SimConfig::stats_interval` ticks
+#[derive(Default, Serialize, Deserialize)]
+pub struct Stats {
+	history: Vec<Sample>,
+
+	births  : usize,
+	#[serde(default)]
+	matings : usize,
+	spawns  : usize,
+	starved : usize,
+	eaten   : usize,
+	#[serde(default)]
+	foraged : usize
+}
+
+impl Stats {
+	pub fn history(&self) -> &[Sample] {&self.history}
+
+	pub fn record_birth(&mut self) {self.births += 1}
+	pub fn record_mating(&mut self) {self.matings += 1}
+	pub fn record_spawn(&mut self) {self.spawns += 1}
+	pub fn record_foraging(&mut self, pellets: usize) {self.foraged += pellets}
+
+	pub fn record_death(&mut self, cause: Death) {
+		match cause {
+			Death::Starved => self.starved += 1,
+			Death::Eaten   => self.eaten   += 1
+		}
+	}
+
+	pub fn sample(&mut self, tick: u64, agents: &[Agent]) {
+		let n    = agents.len().max(1) as f64;
+		let mean = |f: &dyn Fn(&Agent) -> f64| agents.iter().map(f).sum::<f64>() / n;
+
+		let hidden    = agents.iter().map(|a| a.brain.hidden_count()).sum::<usize>();
+		let reachable = agents.iter().map(|a| a.brain.reachable_count()).sum::<usize>();
+
+		let mut species: Vec<_> = agents.iter().filter_map(|a| a.species).collect();
+		species.sort_unstable();
+		species.dedup();
+
+		self.history.push(Sample {
+			tick,
+			agents: agents.len(),
+
+			mean_size : mean(&|a| a.body.size),
+			max_size  : agents.iter().map(|a| a.body.size).fold(0.0, f64::max),
+
+			mean_generation : mean(&|a| a.brain.generation as f64),
+			max_generation  : agents.iter().map(|a| a.brain.generation).max().unwrap_or(0),
+
+			mean_hidden_neurons : hidden as f64 / n,
+			mean_connections    : mean(&|a| a.brain.conn_count() as f64),
+			reachable_ratio     : if hidden > 0 {reachable as f64 / hidden as f64} else {0.0},
+
+			species: species.len(),
+
+			births  : std::mem::take(&mut self.births),
+			matings : std::mem::take(&mut self.matings),
+			spawns  : std::mem::take(&mut self.spawns),
+			starved : std::mem::take(&mut self.starved),
+			eaten   : std::mem::take(&mut self.eaten),
+			foraged : std::mem::take(&mut self.foraged),
+
+			mean_inv_split_freq: mean(&|a| a.inv_split_freq as f64)
+		})
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(&self.history).expect("stats are always serialisable")
+	}
+
+	pub fn to_csv(&self) -> String {
+		let mut s = String::from(
+			"tick,agents,mean_size,max_size,mean_generation,max_generation,\
+			 mean_hidden_neurons,mean_connections,reachable_ratio,species,\
+			 births,matings,spawns,starved,eaten,foraged,mean_inv_split_freq\n"
+		);
+
+		for x in &self.history {
+			s += &format!(
+				"{},{},{:.3},{:.3},{:.3},{},{:.3},{:.3},{:.4},{},{},{},{},{},{},{},{:.3}\n",
+				x.tick, x.agents, x.mean_size, x.max_size, x.mean_generation, x.max_generation,
+				x.mean_hidden_neurons, x.mean_connections, x.reachable_ratio, x.species,
+				x.births, x.matings, x.spawns, x.starved, x.eaten, x.foraged, x.mean_inv_split_freq
+			)
+		}
+
+		s
+	}
+}
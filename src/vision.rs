@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::*, config::SimConfig, spatial::Grid};
+
+/// Rays cast from the centre of an agent, spread evenly across its field of view
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisionConfig {
+	pub rays   : usize, // 0 to disable
+	pub fov    : f64,   // field of view in degrees, centred on the heading
+	pub range  : f64,   // how far rays reach
+	pub colour : bool,  // also sense the colour of agents hit
+
+	pub replace_nearest: bool // drop the angle-to-nearest & touching-edge inputs in favour of the rays
+}
+
+impl Default for VisionConfig {
+	fn default() -> Self {
+		VisionConfig {
+			rays   : 0,
+			fov    : 120.0,
+			range  : 200.0,
+			colour : false,
+
+			replace_nearest: false
+		}
+	}
+}
+
+/// What can be sensed along a ray
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RayChannel {
+	Dist, // closeness of the first hit, 1 when touching & 0 when out of range
+	Wall,
+	Bigger,
+	Smaller,
+	Red,
+	Green,
+	Blue
+}
+
+impl RayChannel {
+	/// The channels enabled by the config
+	pub(crate) fn layout(cfg: &VisionConfig) -> &'static [RayChannel] {
+		use RayChannel::*;
+
+		match cfg.colour {
+			true => &[Dist, Wall, Bigger, Smaller, Red, Green, Blue],
+			_    => &[Dist, Wall, Bigger, Smaller]
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Hit {
+	Wall,
+	Bigger(f64, f64, f64), // colour, normalised to [0, 1]
+	Smaller(f64, f64, f64)
+}
+
+/// What the first thing hit by each ray is, and how far away
+#[derive(Default)]
+pub struct Vision {rays: Vec<Option<(f64, Hit)>>}
+
+////////////////////////////////
+
+impl Vision {
+	pub fn of(agents: &[Agent], i: usize, grid: &Grid, cfg: &SimConfig) -> Vision {
+		let vision = &cfg.vision;
+		if vision.rays == 0 {
+			return Vision::default()
+		}
+
+		let body   = &agents[i].body;
+		let origin = body.pos;
+		let range  = vision.range;
+
+		let mut candidates = vec![];
+		grid.overlapping(origin, range, &mut candidates);
+		candidates.retain(|&j| j != i);
+
+		let fov = vision.fov.to_radians();
+		let rays = (0..vision.rays).map(|r| {
+			let angle = match vision.rays {
+				1 => body.angle,
+				n => body.angle + fov*(r as f64 / (n - 1) as f64 - 0.5)
+			};
+			let dir = (libm::cos(angle), libm::sin(angle));
+
+			// Nearest hit, ties going to the lowest index, walls last
+			let agent_hit = candidates.iter()
+				.filter_map(|&j| ray_hit(origin, dir, &agents[j].body, cfg).map(|dist| (dist, j)))
+				.min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+			let wall_dist = wall_hit(origin, dir, cfg);
+
+			let hit = match agent_hit {
+				Some((dist, j)) if dist <= wall_dist => {
+					let other = &agents[j].body;
+					let Colour {r, g, b} = other.colour;
+					let rgb = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+
+					let hit = if other.size > body.size {Hit::Bigger(rgb.0, rgb.1, rgb.2)} else {Hit::Smaller(rgb.0, rgb.1, rgb.2)};
+					(dist, hit)
+				}
+				_ => (wall_dist, Hit::Wall)
+			};
+
+			Some(hit).filter(|&(dist, _)| dist <= range)
+		}).collect();
+
+		Vision {rays}
+	}
+
+	/// Sensed value of a ray's channel, 0 if the ray hit nothing in range
+	pub fn sense(&self, ray: usize, channel: RayChannel, cfg: &SimConfig) -> f64 {
+		let Some(&Some((dist, hit))) = self.rays.get(ray) else {return 0.0};
+
+		let colour = match hit {
+			Hit::Wall                                 => None,
+			Hit::Bigger(r, g, b) | Hit::Smaller(r, g, b) => Some((r, g, b))
+		};
+
+		match channel {
+			RayChannel::Dist    => 1.0 - dist / cfg.vision.range,
+			RayChannel::Wall    => matches!(hit, Hit::Wall).into(),
+			RayChannel::Bigger  => matches!(hit, Hit::Bigger(..)).into(),
+			RayChannel::Smaller => matches!(hit, Hit::Smaller(..)).into(),
+			RayChannel::Red     => colour.map_or(0.0, |c| c.0),
+			RayChannel::Green   => colour.map_or(0.0, |c| c.1),
+			RayChannel::Blue    => colour.map_or(0.0, |c| c.2)
+		}
+	}
+}
+
+// Distance along the ray to the body, 0 if starting inside it
+fn ray_hit(origin: Pos, (dx, dy): (f64, f64), body: &Body, cfg: &SimConfig) -> Option<f64> {
+	let (ox, oy) = origin.delta(body.pos, cfg);
+	let r = body.radius();
+
+	let along = ox*dx + oy*dy;              // of the centre, projected onto the ray
+	let miss  = ox*ox + oy*oy - along*along; // squared distance of the centre from the ray
+	if miss > r*r {return None}
+
+	let half_chord = (r*r - miss).sqrt();
+	let (t_enter, t_exit) = (along - half_chord, along + half_chord);
+
+	(t_exit >= 0.0).then_some(t_enter.max(0.0))
+}
+
+// Distance along the ray to the edge of the game area, if it has any
+fn wall_hit(origin: Pos, (dx, dy): (f64, f64), cfg: &SimConfig) -> f64 {
+	if cfg.wrap {
+		return f64::INFINITY
+	}
+
+	let axis = |o: f64, d: f64, extent: f64| match d {
+		d if d > 0.0 => (extent - o) / d,
+		d if d < 0.0 => -o / d,
+		_            => f64::INFINITY
+	};
+
+	axis(origin.x, dx, cfg.width).min(axis(origin.y, dy, cfg.height)).max(0.0)
+}
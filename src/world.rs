@@ -0,0 +1,340 @@
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{agent::*, config::SimConfig, crossover::Innovations, food::Food, genome::Genome, helpers::*, js::*};
+use crate::{hall_of_fame::HallOfFame, lineage::Lineage, metabolism, snapshot, spatial::Grid, species::Speciation, stats::Stats};
+use crate::game::update_game;
+use crate::ai::update_ai;
+
+/// A self-contained simulation; any number of these can run side by side
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
+pub struct World {
+	agents: Vec<Agent>,
+	config: SimConfig,
+
+	tick : u64,
+	seed : u64,
+	rng  : SimRng,
+
+	#[serde(default)]
+	stats: Stats,
+
+	lineage: Lineage,
+
+	#[serde(default)]
+	innovations: Innovations,
+
+	#[serde(default)]
+	species: Speciation,
+
+	#[serde(default)]
+	hall_of_fame: HallOfFame,
+
+	#[serde(default)]
+	food: Food,
+
+	#[serde(skip)]
+	grid: Grid,
+
+	#[serde(skip)]
+	colour_by_species: bool
+}
+
+#[wasm_bindgen]
+impl World {
+	/// Creates an empty world with the default config
+	#[wasm_bindgen(constructor)]
+	pub fn new(seed: Option<u64>) -> World {
+		World::with_config(SimConfig::default(), seed)
+	}
+
+	/// Creates an empty world with a config given as JSON
+	pub fn from_config_json(json: &str, seed: Option<u64>) -> Result<World, String> {
+		Ok(World::with_config(SimConfig::from_json(json)?, seed))
+	}
+
+	/// Restores a world saved with `save_snapshot`
+	pub fn load_snapshot(s: &str) -> Result<World, String> {
+		snapshot::load(s)
+	}
+
+	/// Serialises the entire world, including its RNG state, to a versioned snapshot
+	pub fn save_snapshot(&self) -> String {
+		snapshot::save(self)
+	}
+
+	/// Advances the simulation by one tick
+	pub fn step(&mut self) {
+		self.tick += 1;
+
+		// Randomly spawn new agents, or descendants of the best agents so far
+		if rand_range(&mut self.rng, 0..self.config.inverse_spawn_rate) == 0 {
+			let (cfg, ids, innov) = (&self.config, &mut self.lineage.ids, &mut self.innovations);
+			let agent = self.hall_of_fame.maybe_spawn(cfg, ids, innov, &mut self.rng)
+				.unwrap_or_else(|| Agent::new(cfg, ids, innov, &mut self.rng));
+
+			self.lineage.record_birth(&agent, self.tick);
+			self.stats.record_spawn();
+			self.agents.push(agent)
+		}
+
+		self.food.grow(&self.config, &mut self.rng);
+
+		let (cfg, ids, innov) = (&self.config, &mut self.lineage.ids, &mut self.innovations);
+		for agent in Agent::maybe_split(&mut self.agents, cfg, &self.species, ids, innov, &mut self.rng) {
+			self.lineage.record_birth(&agent, self.tick);
+			self.stats.record_birth();
+			self.agents.push(agent)
+		}
+
+		let (cfg, ids, innov) = (&self.config, &mut self.lineage.ids, &mut self.innovations);
+		if let Some(agent) = Agent::maybe_mate(&mut self.agents, &mut self.grid, cfg, ids, innov, &mut self.rng) {
+			self.lineage.record_birth(&agent, self.tick);
+			self.stats.record_mating();
+			self.agents.push(agent)
+		}
+
+		update_ai(&mut self.agents, &mut self.grid, &self.food, &self.config);
+		for (agent, cause) in update_game(&mut self.agents, &mut self.grid, &self.config) {
+			self.hall_of_fame.consider(&agent, &self.config.hall_of_fame);
+			self.lineage.record_death(&agent, self.tick, cause);
+			self.stats.record_death(cause)
+		}
+
+		let foraged = self.food.feed(&mut self.agents, &mut self.grid, &self.config);
+		self.stats.record_foraging(foraged);
+
+		let species = &self.config.species;
+		if species.enabled && self.tick.is_multiple_of(species.interval) {
+			self.species.update(&mut self.agents, self.tick, species, &mut self.rng)
+		}
+
+		let interval = self.config.stats_interval;
+		if interval > 0 && self.tick.is_multiple_of(interval) {
+			self.stats.sample(self.tick, &self.agents)
+		}
+	}
+
+	/// Draws the world onto the given 2D canvas context
+	pub fn draw(&self, canvas: &JsValue) {
+		draw_bg(canvas);
+		for pellet in self.food.pellets() {
+			draw_pellet(canvas, pellet.pos.x, pellet.pos.y, pellet.size)
+		}
+
+		for agent in &self.agents {
+			let species = agent.species.filter(|_| self.colour_by_species).and_then(|id| self.species.get(id));
+
+			let &Colour {r, g, b} = species.map_or(&agent.body.colour, |species| &species.colour);
+
+			// Agents straddling the edges of a wrapping world show on both sides
+			for Pos {x, y} in self.copies(&agent.body) {
+				draw_agent(canvas, r, g, b, x, y, agent.body.size, agent.body.angle)
+			}
+		}
+	}
+
+	pub fn print_agent_at(&self, x: f64, y: f64) {
+		for agent in self.agents_at(x, y) {
+			let mut parent = agent.parent.map_or("none".into(), |id| format!("#{id}"));
+			if let Some(mate) = agent.mate {
+				parent += &format!(" & #{mate}")
+			}
+
+			let network = format!(
+				"Agent #{} (parent: {parent}), Neural Network @ ({x}, {y}): {:#?}", agent.id, agent.brain
+			);
+
+			console_log!("{network}\n\nAGENTS ALIVE: {}", self.agents.len());
+			draw_neural_network(network);
+		}
+	}
+
+	/// Exports the brain of the topmost agent at (x, y) as a JSON genome
+	pub fn export_genome_at(&self, x: f64, y: f64) -> Option<String> {
+		self.agents_at(x, y).last().map(|agent| Genome::of(&agent.brain).to_json())
+	}
+
+	/// Exports the brain of the topmost agent at (x, y) as a binary genome
+	pub fn export_genome_bytes_at(&self, x: f64, y: f64) -> Option<Vec<u8>> {
+		self.agents_at(x, y).last().map(|agent| Genome::of(&agent.brain).to_bytes())
+	}
+
+	/// Spawns a new agent with a brain imported from a JSON genome
+	pub fn spawn_from_genome(&mut self, json: &str) -> Result<(), String> {
+		self.spawn_genome(&Genome::from_json(json)?)
+	}
+
+	/// Spawns a new agent with a brain imported from a binary genome
+	pub fn spawn_from_genome_bytes(&mut self, bytes: &[u8]) -> Result<(), String> {
+		self.spawn_genome(&Genome::from_bytes(bytes)?)
+	}
+
+	/// Genealogy of all agents as Newick trees
+	pub fn lineage_newick(&self) -> String {self.lineage.to_newick()}
+
+	/// Genealogy of all agents as JSON
+	pub fn lineage_json(&self) -> String {self.lineage.to_json()}
+
+	/// Population statistics history as CSV
+	pub fn stats_csv(&self) -> String {self.stats.to_csv()}
+
+	/// Population statistics history as JSON
+	pub fn stats_json(&self) -> String {self.stats.to_json()}
+
+	/// Best agents so far with their scores & genomes, as JSON
+	pub fn hall_of_fame_json(&self) -> String {self.hall_of_fame.to_json()}
+
+	/// Every species so far with its member counts over time, as JSON
+	pub fn species_json(&self) -> String {self.species.to_json()}
+
+	#[wasm_bindgen(getter)]
+	pub fn config_json(&self) -> String {self.config.to_json()}
+
+	/// Replaces the config of a running world
+	pub fn set_config_json(&mut self, json: &str) -> Result<(), String> {
+		self.set_config(SimConfig::from_json(json)?);
+		Ok(())
+	}
+
+	#[wasm_bindgen(getter)]
+	pub fn inverse_spawn_rate(&self) -> usize {self.config.inverse_spawn_rate}
+
+	#[wasm_bindgen(setter)]
+	pub fn set_inverse_spawn_rate(&mut self, rate: usize) {
+		self.config.inverse_spawn_rate = rate.max(1)
+	}
+
+	/// Whether to draw agents in the colour of their species' founder instead of their own
+	#[wasm_bindgen(getter)]
+	pub fn colour_by_species(&self) -> bool {self.colour_by_species}
+
+	#[wasm_bindgen(setter)]
+	pub fn set_colour_by_species(&mut self, enabled: bool) {
+		self.colour_by_species = enabled
+	}
+
+	#[wasm_bindgen(getter)]
+	pub fn width(&self) -> f64 {self.config.width}
+
+	#[wasm_bindgen(getter)]
+	pub fn height(&self) -> f64 {self.config.height}
+
+	#[wasm_bindgen(getter)]
+	pub fn tick(&self) -> u64 {self.tick}
+
+	#[wasm_bindgen(getter)]
+	pub fn seed(&self) -> u64 {self.seed}
+
+	#[wasm_bindgen(getter)]
+	pub fn population(&self) -> usize {self.agents.len()}
+}
+
+impl World {
+	/// Creates an empty world; runs with the same seed & config replay identically on native & wasm
+	pub fn with_config(config: SimConfig, seed: Option<u64>) -> World {
+		let seed = seed.unwrap_or_else(rand::random);
+
+		World {
+			agents: vec![],
+			config,
+
+			tick: 0,
+			seed,
+			rng: SimRng::seed_from_u64(seed),
+
+			stats: Stats::default(),
+
+			lineage: Lineage::default(),
+
+			innovations: Innovations::default(),
+
+			species: Speciation::default(),
+
+			hall_of_fame: HallOfFame::default(),
+
+			food: Food::default(),
+
+			grid: Grid::default(),
+
+			colour_by_species: false
+		}
+	}
+
+	pub fn agents(&self) -> &[Agent] {&self.agents}
+
+	pub fn config(&self) -> &SimConfig {&self.config}
+
+	pub fn stats(&self) -> &Stats {&self.stats}
+
+	pub fn lineage(&self) -> &Lineage {&self.lineage}
+
+	pub fn species(&self) -> &Speciation {&self.species}
+
+	pub fn hall_of_fame(&self) -> &HallOfFame {&self.hall_of_fame}
+
+	pub fn food(&self) -> &Food {&self.food}
+
+	/// Replaces the config, adapting existing brains to a changed set of inputs & outputs
+	pub fn set_config(&mut self, config: SimConfig) {
+		let metabolism_was_enabled = self.config.metabolism.enabled;
+		self.config = config;
+
+		// The size of the world or its grid cells may have changed
+		self.food.reindex(&self.config);
+
+		for agent in &mut self.agents {
+			agent.brain.fit_io(&self.config, &mut self.innovations, &mut self.rng);
+
+			// Agents that lived without a metabolism start out with a fresh energy store
+			if !metabolism_was_enabled {
+				agent.body.energy = metabolism::birth_energy(agent.body.size, &self.config.metabolism)
+			}
+		}
+	}
+
+	pub fn spawn_genome(&mut self, genome: &Genome) -> Result<(), String> {
+		let agent = Agent::from_brain(genome.to_brain()?, &self.config, &mut self.lineage.ids, &mut self.innovations, &mut self.rng);
+
+		self.lineage.record_birth(&agent, self.tick);
+		self.agents.push(agent);
+		Ok(())
+	}
+
+	// Where to draw a body, more than once if it sticks out over an edge it wraps across
+	fn copies(&self, body: &Body) -> Vec<Pos> {
+		let r = body.radius();
+
+		let shifts = |c: f64, extent: f64| {
+			let mut shifts = vec![0.0];
+			if self.config.wrap && c - r < 0.0    {shifts.push(extent)}
+			if self.config.wrap && c + r > extent {shifts.push(-extent)}
+			shifts
+		};
+
+		let (xs, ys) = (shifts(body.pos.x, self.config.width), shifts(body.pos.y, self.config.height));
+		ys.iter().flat_map(|dy| xs.iter().map(move |dx| Pos {x: body.pos.x + dx, y: body.pos.y + dy})).collect()
+	}
+
+	fn agents_at(&self, x: f64, y: f64) -> impl Iterator<Item = &Agent> {
+		self.agents.iter().filter(move |agent| agent.body.pos.dist(Pos {x, y}, &self.config) < agent.body.radius())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn prints_agents_natively() {
+		let mut world = World::new(Some(1));
+		while world.agents.is_empty() {
+			world.step()
+		}
+
+		let Pos {x, y} = world.agents[0].body.pos;
+		world.print_agent_at(x, y)
+	}
+}
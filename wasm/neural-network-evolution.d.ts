@@ -4,24 +4,171 @@
 */
 export function start(): void;
 /**
-* @param {number} inverse_spawn_rate
+* A self-contained simulation; any number of these can run side by side
 */
-export function run(inverse_spawn_rate: number): void;
+export class World {
+  free(): void;
 /**
+* Population statistics history as JSON
+* @returns {string}
+*/
+  stats_json(): string;
+/**
+* Genealogy of all agents as JSON
+* @returns {string}
+*/
+  lineage_json(): string;
+/**
+* Every species so far with its member counts over time, as JSON
+* @returns {string}
+*/
+  species_json(): string;
+/**
+* Restores a world saved with `save_snapshot`
+* @param {string} s
+* @returns {World}
+*/
+  static load_snapshot(s: string): World;
+/**
+* Serialises the entire world, including its RNG state, to a versioned snapshot
+* @returns {string}
+*/
+  save_snapshot(): string;
+/**
+* Genealogy of all agents as Newick trees
+* @returns {string}
+*/
+  lineage_newick(): string;
+/**
+* @param {number} x
+* @param {number} y
+*/
+  print_agent_at(x: number, y: number): void;
+/**
+* Replaces the config of a running world
+* @param {string} json
+*/
+  set_config_json(json: string): void;
+/**
+* Exports the brain of the topmost agent at (x, y) as a JSON genome
+* @param {number} x
+* @param {number} y
+* @returns {string | undefined}
+*/
+  export_genome_at(x: number, y: number): string | undefined;
+/**
+* Creates an empty world with a config given as JSON
+* @param {string} json
+* @param {bigint | undefined} [seed]
+* @returns {World}
+*/
+  static from_config_json(json: string, seed?: bigint): World;
+/**
+* Best agents so far with their scores & genomes, as JSON
+* @returns {string}
+*/
+  hall_of_fame_json(): string;
+/**
+* Spawns a new agent with a brain imported from a JSON genome
+* @param {string} json
+*/
+  spawn_from_genome(json: string): void;
+/**
+* Exports the brain of the topmost agent at (x, y) as a binary genome
 * @param {number} x
 * @param {number} y
+* @returns {Uint8Array | undefined}
 */
-export function print_agent_at(x: number, y: number): void;
+  export_genome_bytes_at(x: number, y: number): Uint8Array | undefined;
+/**
+* Spawns a new agent with a brain imported from a binary genome
+* @param {Uint8Array} bytes
+*/
+  spawn_from_genome_bytes(bytes: Uint8Array): void;
+/**
+* Creates an empty world with the default config
+* @param {bigint | undefined} [seed]
+*/
+  constructor(seed?: bigint);
+/**
+* Draws the world onto the given 2D canvas context
+* @param {any} canvas
+*/
+  draw(canvas: any): void;
+/**
+* Advances the simulation by one tick
+*/
+  step(): void;
+/**
+* Population statistics history as CSV
+* @returns {string}
+*/
+  stats_csv(): string;
+/**
+* Whether to draw agents in the colour of their species' founder instead of their own
+*/
+  colour_by_species: boolean;
+/**
+*/
+  readonly config_json: string;
+/**
+*/
+  readonly height: number;
+/**
+*/
+  inverse_spawn_rate: number;
+/**
+*/
+  readonly population: number;
+/**
+*/
+  readonly seed: bigint;
+/**
+*/
+  readonly tick: bigint;
+/**
+*/
+  readonly width: number;
+}
 
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
 
 export interface InitOutput {
   readonly memory: WebAssembly.Memory;
   readonly start: () => void;
-  readonly run: (a: number) => void;
-  readonly print_agent_at: (a: number, b: number) => void;
-  readonly main: (a: number, b: number) => number;
+  readonly __wbg_world_free: (a: number, b: number) => void;
+  readonly world_colour_by_species: (a: number) => number;
+  readonly world_config_json: (a: number, b: number) => void;
+  readonly world_draw: (a: number, b: number) => void;
+  readonly world_export_genome_at: (a: number, b: number, c: number, d: number) => void;
+  readonly world_export_genome_bytes_at: (a: number, b: number, c: number, d: number) => void;
+  readonly world_from_config_json: (a: number, b: number, c: number, d: number, e: number) => void;
+  readonly world_hall_of_fame_json: (a: number, b: number) => void;
+  readonly world_height: (a: number) => number;
+  readonly world_inverse_spawn_rate: (a: number) => number;
+  readonly world_lineage_json: (a: number, b: number) => void;
+  readonly world_lineage_newick: (a: number, b: number) => void;
+  readonly world_load_snapshot: (a: number, b: number, c: number) => void;
+  readonly world_new: (a: number, b: number) => number;
+  readonly world_population: (a: number) => number;
+  readonly world_print_agent_at: (a: number, b: number, c: number) => void;
+  readonly world_save_snapshot: (a: number, b: number) => void;
+  readonly world_seed: (a: number) => number;
+  readonly world_set_colour_by_species: (a: number, b: number) => void;
+  readonly world_set_config_json: (a: number, b: number, c: number, d: number) => void;
+  readonly world_set_inverse_spawn_rate: (a: number, b: number) => void;
+  readonly world_spawn_from_genome: (a: number, b: number, c: number, d: number) => void;
+  readonly world_spawn_from_genome_bytes: (a: number, b: number, c: number, d: number) => void;
+  readonly world_species_json: (a: number, b: number) => void;
+  readonly world_stats_csv: (a: number, b: number) => void;
+  readonly world_stats_json: (a: number, b: number) => void;
+  readonly world_step: (a: number) => void;
+  readonly world_tick: (a: number) => number;
+  readonly world_width: (a: number) => number;
+  readonly __wbindgen_add_to_stack_pointer: (a: number) => number;
   readonly __wbindgen_free: (a: number, b: number, c: number) => void;
+  readonly __wbindgen_malloc: (a: number, b: number) => number;
+  readonly __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
   readonly __wbindgen_exn_store: (a: number) => void;
   readonly __wbindgen_start: () => void;
 }
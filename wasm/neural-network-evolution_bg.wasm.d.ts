@@ -2,9 +2,38 @@
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
 export function start(): void;
-export function run(a: number): void;
-export function print_agent_at(a: number, b: number): void;
-export function main(a: number, b: number): number;
+export function __wbg_world_free(a: number, b: number): void;
+export function world_colour_by_species(a: number): number;
+export function world_config_json(a: number, b: number): void;
+export function world_draw(a: number, b: number): void;
+export function world_export_genome_at(a: number, b: number, c: number, d: number): void;
+export function world_export_genome_bytes_at(a: number, b: number, c: number, d: number): void;
+export function world_from_config_json(a: number, b: number, c: number, d: number, e: number): void;
+export function world_hall_of_fame_json(a: number, b: number): void;
+export function world_height(a: number): number;
+export function world_inverse_spawn_rate(a: number): number;
+export function world_lineage_json(a: number, b: number): void;
+export function world_lineage_newick(a: number, b: number): void;
+export function world_load_snapshot(a: number, b: number, c: number): void;
+export function world_new(a: number, b: number): number;
+export function world_population(a: number): number;
+export function world_print_agent_at(a: number, b: number, c: number): void;
+export function world_save_snapshot(a: number, b: number): void;
+export function world_seed(a: number): number;
+export function world_set_colour_by_species(a: number, b: number): void;
+export function world_set_config_json(a: number, b: number, c: number, d: number): void;
+export function world_set_inverse_spawn_rate(a: number, b: number): void;
+export function world_spawn_from_genome(a: number, b: number, c: number, d: number): void;
+export function world_spawn_from_genome_bytes(a: number, b: number, c: number, d: number): void;
+export function world_species_json(a: number, b: number): void;
+export function world_stats_csv(a: number, b: number): void;
+export function world_stats_json(a: number, b: number): void;
+export function world_step(a: number): void;
+export function world_tick(a: number): number;
+export function world_width(a: number): number;
+export function __wbindgen_add_to_stack_pointer(a: number): number;
 export function __wbindgen_free(a: number, b: number, c: number): void;
+export function __wbindgen_malloc(a: number, b: number): number;
+export function __wbindgen_realloc(a: number, b: number, c: number, d: number): number;
 export function __wbindgen_exn_store(a: number): void;
 export function __wbindgen_start(): void;